@@ -0,0 +1,192 @@
+use serde_json::{json, Map, Value};
+use voca_rs::case;
+use crate::specs::{SchemaSpec, RefOr, AdditionalPropertiesSpec, ComponentsSpec};
+use super::models::SCHEMA_PREFIX;
+
+/// Where an example is shown, which decides the properties it leaves out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Direction {
+    /// Sent by the client, without read only properties
+    Request,
+    /// Sent by the server, without write only properties
+    Response,
+    /// The schema on its own with all of its properties
+    Schema,
+}
+
+/// Builds an example of a schema from its examples, defaults and enums,
+/// with placeholders of the right type for everything else. References
+/// that are already being expanded are left out to end cycles.
+pub(super) fn example_value(
+    spec: &RefOr<SchemaSpec>,
+    components: Option<&ComponentsSpec>,
+    direction: Direction,
+) -> Value {
+    example_of(spec, components, direction, &mut vec![]).unwrap_or(Value::Null)
+}
+
+fn example_of<'a>(
+    spec: &'a RefOr<SchemaSpec>,
+    components: Option<&'a ComponentsSpec>,
+    direction: Direction,
+    expanding: &mut Vec<&'a str>,
+) -> Option<Value> {
+    match spec {
+        RefOr::Ref { ref ref_path } => example_ref(ref_path, components, direction, expanding),
+        RefOr::Object(ref it) => example_schema(it, components, direction, expanding),
+    }
+}
+
+fn example_ref<'a>(
+    ref_path: &'a str,
+    components: Option<&'a ComponentsSpec>,
+    direction: Direction,
+    expanding: &mut Vec<&'a str>,
+) -> Option<Value> {
+    if expanding.contains(&ref_path) {
+        return None;
+    }
+
+    let schema = ref_path.strip_prefix(SCHEMA_PREFIX)
+        .and_then(|name| components?.schemas.get(name))?;
+    expanding.push(ref_path);
+    let value = example_of(schema, components, direction, expanding);
+    expanding.pop();
+    value
+}
+
+fn example_schema<'a>(
+    spec: &'a SchemaSpec,
+    components: Option<&'a ComponentsSpec>,
+    direction: Direction,
+    expanding: &mut Vec<&'a str>,
+) -> Option<Value> {
+    if let Some(ref it) = spec.example {
+        return Some(it.clone());
+    }
+    if let Some(ref it) = spec.default {
+        return Some(it.clone());
+    }
+    if let Some(it) = spec.schema_enum.iter().find(|it| !it.is_null()) {
+        return Some(it.clone());
+    }
+
+    if !spec.all_of.is_empty() {
+        let mut merged = Map::new();
+        for part in spec.all_of.iter() {
+            if let Some(Value::Object(it)) = example_of(part, components, direction, expanding) {
+                merged.extend(it);
+            }
+        }
+        if let Some(Value::Object(it)) = example_object(spec, components, direction, expanding) {
+            merged.extend(it);
+        }
+        return Some(Value::Object(merged));
+    }
+    if let Some(part) = spec.one_of.first().or_else(|| spec.any_of.first()) {
+        let mut value = example_of(part, components, direction, expanding);
+        // the discriminator has to name the variant the example is built from
+        if let (Some(Value::Object(it)), Some(discriminator), RefOr::Ref { ref ref_path }) = (&mut value, &spec.discriminator, part) {
+            let schema = ref_path.rsplit('/').next().unwrap_or_default();
+            let tag = discriminator.mapping.iter()
+                .find(|(_, target)| *target == ref_path || target.as_str() == schema)
+                .map_or(schema, |(tag, _)| tag.as_str());
+            it.insert(discriminator.property_name.clone(), Value::from(tag));
+        }
+        return value;
+    }
+
+    let value = match (spec.schema_type.as_deref(), spec.format.as_deref()) {
+        (Some("string"), Some("date-time")) => json!("2024-01-01T00:00:00Z"),
+        (Some("string"), Some("date")) => json!("2024-01-01"),
+        (Some("string"), Some("uuid")) => json!("3fa85f64-5717-4562-b3fc-2c963f66afa6"),
+        (Some("string"), Some("email")) => json!("user@example.com"),
+        (Some("string"), Some("uri")) | (Some("string"), Some("url")) => json!("https://example.com"),
+        (Some("string"), _) => json!("string"),
+        (Some("integer"), _) => json!(spec.lower_bound().map_or(0, |(it, exclusive)| it as i64 + exclusive as i64)),
+        (Some("number"), _) => json!(spec.lower_bound().map_or(0.0, |(it, _)| it)),
+        (Some("boolean"), _) => json!(true),
+        (Some("array"), _) => {
+            let items = match spec.items {
+                Some(RefOr::Ref { ref ref_path }) => example_ref(ref_path, components, direction, expanding),
+                Some(RefOr::Object(ref it)) => example_schema(it, components, direction, expanding),
+                None => None,
+            };
+            Value::from(items.into_iter().collect::<Vec<_>>())
+        },
+        _ => return example_object(spec, components, direction, expanding),
+    };
+
+    Some(value)
+}
+
+fn example_object<'a>(
+    spec: &'a SchemaSpec,
+    components: Option<&'a ComponentsSpec>,
+    direction: Direction,
+    expanding: &mut Vec<&'a str>,
+) -> Option<Value> {
+    let mut object = Map::new();
+    for (name, property) in spec.properties.iter() {
+        let skipped = property.resolve(components).is_some_and(|it| match direction {
+            Direction::Request => it.read_only,
+            Direction::Response => it.write_only,
+            Direction::Schema => false,
+        });
+        if skipped {
+            continue;
+        }
+
+        if let Some(value) = example_of(property, components, direction, expanding) {
+            object.insert(name.clone(), value);
+        }
+    }
+
+    if let Some(AdditionalPropertiesSpec::Schema(ref it)) = spec.additional_properties {
+        if let Some(value) = example_of(it, components, direction, expanding) {
+            object.insert(String::from("key"), value);
+        }
+    }
+
+    Some(Value::Object(object))
+}
+
+pub(super) fn tag_slug(tag: &str) -> String {
+    match case::kebab_case(tag).as_str() {
+        "" => String::from("default"),
+        it => String::from(it),
+    }
+}
+
+/// The reason phrase of a status code, ranges and `default` included.
+pub(super) fn reason(status: &str) -> Option<String> {
+    let reason = match status {
+        "200" => "OK",
+        "201" => "Created",
+        "202" => "Accepted",
+        "204" => "No Content",
+        "301" => "Moved Permanently",
+        "302" => "Found",
+        "304" => "Not Modified",
+        "400" => "Bad Request",
+        "401" => "Unauthorized",
+        "403" => "Forbidden",
+        "404" => "Not Found",
+        "405" => "Method Not Allowed",
+        "409" => "Conflict",
+        "410" => "Gone",
+        "412" => "Precondition Failed",
+        "415" => "Unsupported Media Type",
+        "422" => "Unprocessable Entity",
+        "429" => "Too Many Requests",
+        "500" => "Internal Server Error",
+        "502" => "Bad Gateway",
+        "503" => "Service Unavailable",
+        "504" => "Gateway Timeout",
+        "default" => "Any other status",
+        it if it.len() == 3 && (it.ends_with("XX") || it.ends_with("xx")) => "Any status of the range",
+        _ => return None,
+    };
+
+    Some(String::from(reason))
+}
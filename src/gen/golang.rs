@@ -0,0 +1,861 @@
+use std::collections::{BTreeMap, HashSet};
+use serde_derive::Serialize;
+use voca_rs::case;
+use crate::{
+    specs::{
+        self, OpenApiSpec, SchemaSpec, RefOr, ComponentsSpec, PathSpec, OperationSpec,
+    },
+    gen::{
+        Generator, Options, TemplateContext,
+        typescript::{success_response, unsupported_body, ResponseType},
+        models::{self, Language, schema_type, non_empty, discriminator_value, snake_ident},
+    },
+    util,
+};
+
+/// Generates a go package with structs for all schemas and a client with a
+/// method per operation, in a file per tag.
+pub struct GoGenerator;
+impl Generator for GoGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates("go").unwrap();
+        let package: String = spec.info.title.to_lowercase().chars()
+            .filter(|it| it.is_ascii_alphanumeric())
+            .collect();
+        let package = match package.as_str() {
+            "" => String::from("client"),
+            it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("api{}", it),
+            _ => package,
+        };
+
+        let mut builder = ModelBuilder::new(spec.components.as_ref(), Go { enums: HashSet::new() });
+        builder.add_schemas();
+
+        let files = generate_files(spec, &package, &mut builder);
+        let schemes = spec.components.as_ref()
+            .map_or_else(Vec::new, |it| models::security_schemes(it, go_name));
+        let lines: Vec<(bool, Vec<&str>)> = schemes.iter()
+            .map(|it| (it.description.as_deref().is_some_and(|it| !it.is_empty()), vec![it.field.as_str()]))
+            .collect();
+        let paddings = align(&lines);
+        let schemes = schemes.into_iter().zip(paddings)
+            .map(|(scheme, mut padding)| Credential { scheme, name_pad: padding.remove(0) })
+            .collect();
+
+        let servers = spec.servers.iter()
+            .map(|server| {
+                server.variables.iter().fold(server.url.clone(), |url, (name, it)| {
+                    url.replace(&format!("{{{}}}", name), &it.default)
+                })
+            })
+            .collect();
+
+        let mut models = builder.models;
+        models.iter_mut().for_each(Model::align);
+        let models_file = ModelsFile {
+            package: package.clone(),
+            uses_time: models.iter().any(|it| it.uses_time()),
+            has_unions: models.iter().any(|it| matches!(it, Model::Union { .. })),
+            models,
+        };
+
+        util::write_templates(&templates, &vec![ModFile { module: package.clone() }], None).unwrap();
+        util::write_templates(&templates, &vec![models_file], None).unwrap();
+        util::write_templates(&templates, &vec![ClientFile { package, schemes, servers }], None).unwrap();
+        util::write_templates(&templates, &files, None).unwrap();
+    }
+}
+
+/// The go side of the models. Unions are structs with a pointer per variant
+/// and intersections embed their referenced parts.
+struct Go {
+    /// Named types with a primitive underlying type, which can be formatted
+    /// as parameters
+    enums: HashSet<String>,
+}
+
+type ModelBuilder<'a> = models::ModelBuilder<'a, Go>;
+
+impl Go {
+    /// Whether values of the type can be formatted as parameters directly.
+    fn is_display(&self, go_type: &str) -> bool {
+        match go_type {
+            "string" | "bool" | "int32" | "int64" | "float32" | "float64" | "time.Time" => true,
+            it => self.enums.contains(it),
+        }
+    }
+}
+
+impl Language for Go {
+    type Model = Model;
+
+    const ANY: &'static str = "any";
+
+    fn primitive_type(spec: &SchemaSpec) -> String {
+        let go_type = match (schema_type(spec), spec.format.as_deref()) {
+            ("string", Some("date-time")) => "time.Time",
+            ("string", _) => "string",
+            ("integer", Some("int32")) => "int32",
+            ("integer", _) => "int64",
+            ("number", Some("float")) => "float32",
+            ("number", _) => "float64",
+            ("boolean", _) => "bool",
+            _ => Self::ANY,
+        };
+
+        String::from(go_type)
+    }
+
+    fn array_type(items: &str) -> String {
+        format!("[]{}", items)
+    }
+
+    fn map_type(values: Option<String>) -> String {
+        format!("map[string]{}", values.as_deref().unwrap_or(Self::ANY))
+    }
+
+    fn type_name(name: &str) -> String {
+        type_name(name)
+    }
+
+    fn alias(name: String, description: Option<String>, alias: String) -> Model {
+        Model::Alias { name, description, alias }
+    }
+
+    fn enum_type(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let values: Vec<&serde_json::Value> = spec.schema_enum.iter()
+            .filter(|it| !it.is_null())
+            .collect();
+
+        let base = if !values.is_empty() && values.iter().all(|it| it.is_string()) {
+            String::from("string")
+        } else if !values.is_empty() && values.iter().all(|it| it.is_i64() || it.is_u64()) {
+            Self::primitive_type(spec)
+        } else {
+            return Self::primitive_type(spec);
+        };
+
+        let mut variants: Vec<EnumVariant> = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let text = match value.as_str() {
+                Some(it) => String::from(it),
+                None => value.to_string(),
+            };
+            let member = spec.enum_var_names.get(i).unwrap_or(&text);
+            let base_name = match go_words(member).as_str() {
+                "" if text.starts_with('-') => format!("{}Minus", name),
+                "" => format!("{}Empty", name),
+                it => format!("{}{}", name, it),
+            };
+
+            let mut constant = base_name.clone();
+            let mut suffix = 1;
+            while variants.iter().any(|it| it.name == constant) {
+                suffix += 1;
+                constant = format!("{}{}", base_name, suffix);
+            }
+
+            variants.push(EnumVariant {
+                name: constant,
+                value: value.to_string(),
+                description: spec.enum_descriptions.get(i).filter(|it| !it.is_empty()).cloned(),
+                name_pad: String::new(),
+            });
+        }
+
+        builder.language.enums.insert(String::from(name));
+        builder.models.push(Model::Enum {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            base, variants,
+        });
+
+        String::from(name)
+    }
+
+    fn object(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let mut fields = vec![];
+        add_fields(builder, &mut fields, spec, name);
+
+        builder.models.push(Model::Struct {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            fields,
+        });
+
+        String::from(name)
+    }
+
+    /// Referenced parts of an intersection are embedded, whose fields json
+    /// promotes, inline objects contribute their properties directly.
+    fn all_of(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let mut fields = vec![];
+        for (i, part) in spec.all_of.iter().enumerate() {
+            match part {
+                RefOr::Object(ref it) if !it.properties.is_empty() && it.all_of.is_empty()
+                    && it.one_of.is_empty() && it.any_of.is_empty() => {
+                    add_fields(builder, &mut fields, it, name);
+                },
+                _ => {
+                    let part_type = builder.type_of(part, &format!("{}Part{}", name, i + 1));
+                    fields.push(Field {
+                        name: part_type.clone(),
+                        json_name: String::new(),
+                        field_type: part_type,
+                        omitempty: false,
+                        embedded: true,
+                        description: None,
+                        name_pad: String::new(),
+                        type_pad: String::new(),
+                    });
+                },
+            }
+        }
+        add_fields(builder, &mut fields, spec, name);
+
+        builder.models.push(Model::Struct {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            fields,
+        });
+
+        String::from(name)
+    }
+
+    /// Unions are structs with a pointer per variant, of which one is set.
+    /// A discriminator decides the variant to decode, otherwise the first
+    /// one that matches strictly is used.
+    fn union(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let parts = if spec.one_of.is_empty() { &spec.any_of } else { &spec.one_of };
+
+        let mut variants: Vec<UnionVariant> = vec![];
+        for (i, part) in parts.iter().enumerate() {
+            let (variant, tag) = match part {
+                RefOr::Ref { ref ref_path } => {
+                    let schema = ref_path.rsplit('/').next().unwrap_or_default();
+                    let tag = spec.discriminator.as_ref().map(|it| discriminator_value(it, ref_path));
+                    (type_name(schema), tag)
+                },
+                RefOr::Object(_) => (format!("Variant{}", i + 1), None),
+            };
+
+            let variant_type = builder.type_of(part, &format!("{}Variant{}", name, i + 1));
+            variants.push(UnionVariant {
+                name: variant,
+                variant_type,
+                tag,
+                name_pad: String::new(),
+            });
+        }
+
+        // variants without a discriminator value can't be decoded by it
+        let tag = spec.discriminator.as_ref()
+            .filter(|_| variants.iter().all(|it| it.tag.is_some()))
+            .map(|it| it.property_name.clone());
+
+        builder.models.push(Model::Union {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            tag, variants,
+        });
+
+        String::from(name)
+    }
+}
+
+fn add_fields(builder: &mut ModelBuilder, fields: &mut Vec<Field>, spec: &SchemaSpec, name: &str) {
+    for (property, property_spec) in spec.properties.iter() {
+        let property_type = builder.type_of(property_spec, &format!("{}{}", name, type_name(property)));
+        let required = spec.required.contains(property);
+
+        // absent values of slices, maps and interfaces are nil already,
+        // structs of a cycle have to be pointers to have a size at all
+        let pointer = !is_nilable(&property_type) && (
+            builder.is_cyclic_ref(property_spec) || !required || builder.is_nullable(property_spec)
+        );
+
+        fields.push(Field {
+            name: go_name(property),
+            json_name: property.clone(),
+            field_type: if pointer { format!("*{}", property_type) } else { property_type },
+            omitempty: !required,
+            embedded: false,
+            description: builder.description(property_spec),
+            name_pad: String::new(),
+            type_pad: String::new(),
+        });
+    }
+}
+
+fn generate_files(spec: &OpenApiSpec, package: &str, builder: &mut ModelBuilder) -> Vec<TagFile> {
+    let mut files: BTreeMap<String, Vec<Operation>> = BTreeMap::new();
+    for tag in spec.tags.iter() {
+        files.insert(tag.name.clone(), vec![]);
+    }
+
+    for (path, path_spec) in spec.paths.iter() {
+        for (method, op) in path_spec.operations() {
+            let tag = match op.tags.first() {
+                Some(it) => it,
+                None => {
+                    println!("\tskipping untagged operation: {} ({})", op.operation_id, method);
+                    continue;
+                },
+            };
+
+            if let Some(media_type) = unsupported_body(op, builder.components) {
+                println!(
+                    "\tskipping {} ({}), {} request bodies are not supported",
+                    op.operation_id, method, media_type,
+                );
+                continue;
+            }
+
+            let operation = generate_operation(path, path_spec, method, op, &spec.security, builder);
+            files.entry(tag.clone()).or_default().push(operation);
+        }
+    }
+
+    files.into_iter()
+        .filter(|(_, operations)| !operations.is_empty())
+        .map(|(tag, operations)| {
+            let mut file_name = case::snake_case(&tag);
+            // suffixes like _test or _linux have a meaning to go
+            if file_name.contains('_') || file_name.is_empty() || file_name == "models" || file_name == "client" {
+                file_name.push_str("_api");
+            }
+
+            TagFile {
+                file_name,
+                package: String::from(package),
+                uses_time: operations.iter()
+                    .flat_map(|it| it.path_params.iter().chain(it.params.iter()))
+                    .any(|it| it.param_type.contains("time.Time")),
+                operations,
+            }
+        })
+        .collect()
+}
+
+fn generate_operation(
+    path: &str,
+    path_spec: &PathSpec,
+    method: &str,
+    spec: &OperationSpec,
+    security: &[specs::SecurityRequirementSpec],
+    builder: &mut ModelBuilder,
+) -> Operation {
+    let name = go_name(&spec.operation_id);
+
+    let mut path_params = vec![];
+    let mut params = vec![];
+    for param in path_spec.params(spec) {
+        let param_type = builder.type_of(&param.schema, &format!("{}{}", name, type_name(&param.name)));
+        let items = param_type.strip_prefix("[]");
+        let location = param.location.as_str();
+        if location != "path" && location != "query" && location != "header" {
+            println!("\tskipping {} parameter {} of {}", location, param.name, spec.operation_id);
+            continue;
+        }
+
+        let field = Param {
+            name: param.name.clone(),
+            ident: if location == "path" { arg_name(&param.name) } else { go_name(&param.name) },
+            location: String::from(location),
+            required: param.required || location == "path",
+            format: match items {
+                Some(items) if builder.language.is_display(items) => String::from("joinParam"),
+                None if builder.language.is_display(&param_type) => String::from("paramValue"),
+                _ => String::from("jsonParam"),
+            },
+            pointer: !param.required && !is_nilable(&param_type),
+            description: param.description.as_deref().and_then(non_empty),
+            param_type,
+            name_pad: String::new(),
+        };
+
+        if location == "path" {
+            path_params.push(field);
+        } else {
+            params.push(field);
+        }
+    }
+
+    for placeholder in specs::path_placeholders(path) {
+        if path_params.iter().any(|it: &Param| it.name == placeholder) {
+            continue;
+        }
+
+        println!("\t{} has no parameter for {{{}}}, it takes a string", spec.operation_id, placeholder);
+        path_params.push(Param {
+            name: String::from(placeholder),
+            ident: arg_name(placeholder),
+            location: String::from("path"),
+            param_type: String::from("string"),
+            required: true,
+            pointer: false,
+            format: String::from("paramValue"),
+            description: None,
+            name_pad: String::new(),
+        });
+    }
+    path_params.retain(|it| {
+        let is_placeholder = path.contains(&format!("{{{}}}", it.name));
+        if !is_placeholder {
+            println!(
+                "\tskipping path parameter {} of {}, the path has no placeholder for it",
+                it.name, spec.operation_id,
+            );
+        }
+        is_placeholder
+    });
+
+    // path params are passed in the order of the path, which is joined from
+    // its literal parts and the escaped params
+    path_params.sort_by_key(|it| path.find(&format!("{{{}}}", it.name)));
+    let mut path_expr = vec![];
+    let mut rest = path;
+    for param in path_params.iter() {
+        let placeholder = format!("{{{}}}", param.name);
+        if let Some(i) = rest.find(&placeholder) {
+            if i > 0 {
+                path_expr.push(serde_json::Value::from(&rest[..i]).to_string());
+            }
+            path_expr.push(format!("url.PathEscape({}({}))", param.format, param.ident));
+            rest = &rest[i + placeholder.len()..];
+        }
+    }
+    if !rest.is_empty() || path_expr.is_empty() {
+        path_expr.push(serde_json::Value::from(rest).to_string());
+    }
+
+    let body = spec.request_body.as_ref()
+        .and_then(|it| it.resolve(builder.components))
+        .and_then(|body_spec| {
+            let schema = specs::json_schema(&body_spec.content)?;
+            let body_type = builder.type_of(&schema, &format!("{}Request", name));
+            Some(Body {
+                body_type: if body_spec.required || is_nilable(&body_type) {
+                    body_type
+                } else {
+                    format!("*{}", body_type)
+                },
+                required: body_spec.required,
+            })
+        });
+
+    let (accept, response_schema) = success_response(&spec.responses, builder.components)
+        .unwrap_or_default();
+    let (response_kind, return_type) = match accept.first().map(|it| ResponseType::from_media_type(it)) {
+        None => ("empty", String::new()),
+        Some(ResponseType::Json) => match response_schema {
+            Some(schema) => ("json", builder.type_of(&schema, &format!("{}Response", name))),
+            None => ("json", String::from("any")),
+        },
+        Some(ResponseType::Text) => ("text", String::from("string")),
+        Some(ResponseType::Blob) | Some(ResponseType::ArrayBuffer) => ("bytes", String::from("[]byte")),
+        // the events are read from the body as they arrive
+        Some(ResponseType::EventStream) => ("stream", String::from("*http.Response")),
+    };
+
+    let has_success = spec.responses.keys().any(|it| it.starts_with('2'));
+    let mut errors: Vec<ErrorResponse> = spec.responses.iter()
+        .filter(|(status, _)| {
+            status.starts_with('4') || status.starts_with('5')
+                || (has_success && status.as_str() == "default")
+        })
+        .filter_map(|(status, response)| {
+            let variant = if status == "default" {
+                String::from("Default")
+            } else {
+                format!("Status{}", status.to_uppercase())
+            };
+            let body_type = builder.response_type(response, &format!("{}{}Error", name, variant))?;
+
+            let condition = match status.as_str() {
+                "default" => None,
+                it if it.parse::<u16>().is_ok() => Some(format!("resp.StatusCode == {}", it)),
+                it => {
+                    let class = &it[..1];
+                    Some(format!("resp.StatusCode >= {}00 && resp.StatusCode <= {}99", class, class))
+                },
+            };
+
+            Some(ErrorResponse { condition, body_type })
+        })
+        .collect();
+
+    // exact statuses have to be matched before ranges and the default
+    errors.sort_by_key(|it| match it.condition {
+        None => 2,
+        Some(ref condition) if condition.contains(">=") => 1,
+        Some(_) => 0,
+    });
+
+    let lines: Vec<(bool, Vec<&str>)> = params.iter()
+        .map(|it| (it.description.is_some(), vec![it.ident.as_str()]))
+        .collect();
+    let paddings = align(&lines);
+    for (param, mut padding) in params.iter_mut().zip(paddings) {
+        param.name_pad = padding.remove(0);
+    }
+
+    Operation {
+        name,
+        summary: non_empty(&spec.summary.as_deref().unwrap_or_default().replace('\n', " ")),
+        deprecated: spec.deprecated,
+        method: method.to_uppercase(),
+        path: String::from(path),
+        path_expr: path_expr.join(" + "),
+        path_params, params, body,
+        accept: accept.first().cloned(),
+        response_kind: String::from(response_kind),
+        return_type, errors,
+        security: security_expr(spec.security.as_ref().map_or(security, |it| it.as_slice())),
+    }
+}
+
+/// The requirements as go expression, a slice of the scheme names of each.
+fn security_expr(requirements: &[specs::SecurityRequirementSpec]) -> String {
+    let requirements: Vec<String> = requirements.iter()
+        .filter(|it| !it.is_empty())
+        .map(|it| {
+            let schemes: Vec<String> = it.keys()
+                .map(|name| serde_json::Value::from(name.as_str()).to_string())
+                .collect();
+            format!("{{{}}}", schemes.join(", "))
+        })
+        .collect();
+
+    if requirements.is_empty() {
+        String::from("nil")
+    } else {
+        format!("[][]string{{{}}}", requirements.join(", "))
+    }
+}
+
+/// Whether the zero value of a type is nil, which makes a pointer needless.
+fn is_nilable(go_type: &str) -> bool {
+    go_type.starts_with("[]") || go_type.starts_with("map[") || go_type.starts_with('*') || go_type == "any"
+}
+
+/// Words that are written in upper case in go identifiers.
+const INITIALISMS: &[&str] = &[
+    "ACL", "API", "ASCII", "CPU", "CSS", "DNS", "EOF", "GUID", "HTML", "HTTP", "HTTPS", "ID",
+    "IP", "JSON", "LHS", "QPS", "RAM", "RHS", "RPC", "SLA", "SMTP", "SQL", "SSH", "TCP", "TLS",
+    "TTL", "UDP", "UI", "UID", "UUID", "URI", "URL", "UTF8", "VM", "XML", "XMPP", "XSRF", "XSS",
+];
+
+/// The spaces that align the cells of consecutive lines like gofmt does,
+/// given for every line whether a comment precedes it and its cells up to
+/// the last one. Comments and lines with another number of cells end a block
+/// of aligned lines.
+fn align(lines: &[(bool, Vec<&str>)]) -> Vec<Vec<String>> {
+    let mut paddings = vec![];
+    let mut start = 0;
+    for end in 1..=lines.len() {
+        if end < lines.len() && !lines[end].0 && lines[end].1.len() == lines[start].1.len() {
+            continue;
+        }
+
+        let block = &lines[start..end];
+        let widths: Vec<usize> = (0..block[0].1.len())
+            .map(|i| block.iter().map(|(_, cells)| cells[i].chars().count()).max().unwrap_or(0))
+            .collect();
+        for (_, cells) in block {
+            paddings.push(cells.iter().zip(widths.iter())
+                .map(|(cell, width)| " ".repeat(width - cell.chars().count()))
+                .collect());
+        }
+        start = end;
+    }
+    paddings
+}
+
+/// Converts a name into an exported identifier, e.g. `petId` to `PetID` and
+/// `businessUnit.ancestors` to `BusinessUnitAncestors`.
+fn go_name(name: &str) -> String {
+    let ident = go_words(name);
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("X{}", ident)
+    } else {
+        ident
+    }
+}
+
+/// Joins the words of a name capitalized, which may start with a digit.
+fn go_words(name: &str) -> String {
+    snake_ident(name).split('_').map(go_word).collect()
+}
+
+/// Capitalizes a word, initialisms and their plurals like `IDs` are upper case.
+fn go_word(word: &str) -> String {
+    let upper = word.to_uppercase();
+    if INITIALISMS.contains(&upper.as_str()) {
+        return upper;
+    }
+    match upper.strip_suffix('S') {
+        Some(stem) if INITIALISMS.contains(&stem) => format!("{}s", stem),
+        _ => case::capitalize(word, false),
+    }
+}
+
+fn type_name(name: &str) -> String {
+    match go_name(name).as_str() {
+        "" => String::from("Value"),
+        it => String::from(it),
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough",
+    "for", "func", "go", "goto", "if", "import", "interface", "map", "package", "range",
+    "return", "select", "struct", "switch", "type", "var",
+    // the receiver, locals and packages the methods use
+    "c", "ctx", "body", "payload", "params", "path", "query", "header", "resp", "err",
+    "result", "value", "url", "http", "context", "time",
+];
+
+/// Converts a name into an unexported identifier for arguments, e.g.
+/// `petId` to `petID`.
+fn arg_name(name: &str) -> String {
+    let snake = snake_ident(name);
+    let mut words = snake.split('_').filter(|it| !it.is_empty());
+    let first = words.next().unwrap_or_default().to_lowercase();
+    let ident: String = words.map(go_word).fold(first, |ident, word| ident + &word);
+
+    match ident.as_str() {
+        "" => String::from("value"),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("v{}", it),
+        it if KEYWORDS.contains(&it) => format!("{}Param", it),
+        _ => ident,
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type")]
+enum Model {
+    Struct {
+        name: String,
+        description: Option<String>,
+        fields: Vec<Field>,
+    },
+    Enum {
+        name: String,
+        description: Option<String>,
+        /// The underlying type, `string` or an integer type
+        base: String,
+        variants: Vec<EnumVariant>,
+    },
+    Union {
+        name: String,
+        description: Option<String>,
+        /// The discriminator property, variants are tried in order if there
+        /// is none
+        tag: Option<String>,
+        variants: Vec<UnionVariant>,
+    },
+    Alias {
+        name: String,
+        description: Option<String>,
+        alias: String,
+    },
+}
+
+impl Model {
+    /// Aligns the types of the fields and variants.
+    fn align(&mut self) {
+        match self {
+            Model::Struct { ref mut fields, .. } => {
+                // embedded structs are a single cell
+                let lines: Vec<(bool, Vec<&str>)> = fields.iter()
+                    .map(|it| (it.description.is_some(), if it.embedded {
+                        vec![]
+                    } else {
+                        vec![it.name.as_str(), it.field_type.as_str()]
+                    }))
+                    .collect();
+                let paddings = align(&lines);
+                for (field, padding) in fields.iter_mut().zip(paddings) {
+                    if let [name_pad, type_pad] = padding.as_slice() {
+                        field.name_pad = name_pad.clone();
+                        field.type_pad = type_pad.clone();
+                    }
+                }
+            },
+            Model::Enum { ref mut variants, .. } => {
+                let lines: Vec<(bool, Vec<&str>)> = variants.iter()
+                    .map(|it| (it.description.is_some(), vec![it.name.as_str()]))
+                    .collect();
+                let paddings = align(&lines);
+                for (variant, mut padding) in variants.iter_mut().zip(paddings) {
+                    variant.name_pad = padding.remove(0);
+                }
+            },
+            Model::Union { ref mut variants, .. } => {
+                let lines: Vec<(bool, Vec<&str>)> = variants.iter()
+                    .map(|it| (false, vec![it.name.as_str()]))
+                    .collect();
+                let paddings = align(&lines);
+                for (variant, mut padding) in variants.iter_mut().zip(paddings) {
+                    variant.name_pad = padding.remove(0);
+                }
+            },
+            Model::Alias { .. } => {},
+        }
+    }
+
+    fn uses_time(&self) -> bool {
+        match self {
+            Model::Struct { ref fields, .. } => fields.iter().any(|it| it.field_type.contains("time.Time")),
+            Model::Union { ref variants, .. } => variants.iter().any(|it| it.variant_type.contains("time.Time")),
+            Model::Alias { ref alias, .. } => alias.contains("time.Time"),
+            Model::Enum { .. } => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Field {
+    name: String,
+    json_name: String,
+    field_type: String,
+    omitempty: bool,
+    /// Embedded structs of an intersection
+    embedded: bool,
+    description: Option<String>,
+    /// The spaces that align the type and the tag with the other fields
+    name_pad: String,
+    type_pad: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct EnumVariant {
+    name: String,
+    /// The value as go literal
+    value: String,
+    description: Option<String>,
+    /// The spaces that align the type with the other variants
+    name_pad: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct UnionVariant {
+    name: String,
+    variant_type: String,
+    /// The discriminator value
+    tag: Option<String>,
+    /// The spaces that align the type with the other variants
+    name_pad: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Param {
+    name: String,
+    /// The argument of path params, the field in the params struct of others
+    ident: String,
+    location: String,
+    param_type: String,
+    required: bool,
+    /// Whether the field in the params struct is a pointer
+    pointer: bool,
+    /// The helper formatting the value: paramValue, joinParam or jsonParam
+    format: String,
+    description: Option<String>,
+    /// The spaces that align the type with the other fields of the params
+    /// struct
+    name_pad: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Body {
+    body_type: String,
+    required: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ErrorResponse {
+    /// The condition on the status code, `None` for the default response
+    condition: Option<String>,
+    body_type: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Operation {
+    name: String,
+    summary: Option<String>,
+    deprecated: bool,
+    method: String,
+    path: String,
+    /// Expression of the path with the escaped path params
+    path_expr: String,
+    path_params: Vec<Param>,
+    /// The query and header params
+    params: Vec<Param>,
+    body: Option<Body>,
+    accept: Option<String>,
+    /// How the response body is read: json, text, bytes, stream or empty
+    response_kind: String,
+    return_type: String,
+    errors: Vec<ErrorResponse>,
+    /// The security requirements as go expression
+    security: String,
+}
+
+#[derive(Serialize)]
+struct ModFile {
+    module: String,
+}
+
+impl TemplateContext for ModFile {
+    fn template(&self) -> &'static str { "mod.tera" }
+    fn filename(&self) -> String { String::from("go.mod") }
+}
+
+#[derive(Serialize)]
+struct ModelsFile {
+    package: String,
+    uses_time: bool,
+    has_unions: bool,
+    models: Vec<Model>,
+}
+
+impl TemplateContext for ModelsFile {
+    fn template(&self) -> &'static str { "models.tera" }
+    fn filename(&self) -> String { String::from("models.go") }
+}
+
+/// A security scheme along with its field in the credentials.
+#[derive(Serialize)]
+struct Credential {
+    #[serde(flatten)]
+    scheme: models::SecurityScheme,
+    /// The spaces that align the type with the other fields
+    name_pad: String,
+}
+
+#[derive(Serialize)]
+struct ClientFile {
+    package: String,
+    schemes: Vec<Credential>,
+    /// Server urls with the defaults of their variables
+    servers: Vec<String>,
+}
+
+impl TemplateContext for ClientFile {
+    fn template(&self) -> &'static str { "client.tera" }
+    fn filename(&self) -> String { String::from("client.go") }
+}
+
+#[derive(Serialize)]
+struct TagFile {
+    file_name: String,
+    package: String,
+    uses_time: bool,
+    operations: Vec<Operation>,
+}
+
+impl TemplateContext for TagFile {
+    fn template(&self) -> &'static str { "operations.tera" }
+    fn filename(&self) -> String { format!("{}.go", self.file_name) }
+}
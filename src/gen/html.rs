@@ -0,0 +1,454 @@
+use std::collections::BTreeMap;
+use serde_derive::Serialize;
+use voca_rs::case;
+use crate::{
+    specs::{self, OpenApiSpec, SchemaSpec, RefOr, AdditionalPropertiesSpec, ComponentsSpec, OperationSpec},
+    gen::{Generator, Options, TemplateContext},
+    util,
+};
+use super::models::SCHEMA_PREFIX;
+use super::docs::{Direction, example_value, reason, tag_slug};
+
+/// Nesting of inline schemas that is still expanded in schema trees
+const MAX_DEPTH: usize = 6;
+
+/// Generates a static documentation site that works offline: a single page
+/// with the operations grouped by tag and the schemas, along with the
+/// stylesheet and script it loads.
+pub struct HtmlGenerator;
+impl Generator for HtmlGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates("html").unwrap();
+        let components = spec.components.as_ref();
+
+        let mut operations: BTreeMap<String, Vec<OperationView>> = BTreeMap::new();
+        for (path, path_spec) in spec.paths.iter() {
+            for (method, op) in path_spec.operations() {
+                let tag = op.tags.first().cloned().unwrap_or_else(|| String::from("default"));
+                operations.entry(tag).or_default().push(generate_operation(path, method, op, &spec.security, components));
+            }
+        }
+
+        // declared tags keep their order, the ones only operations use follow
+        let mut tags: Vec<TagSection> = spec.tags.iter()
+            .map(|it| TagSection {
+                name: it.name.clone(),
+                slug: tag_slug(&it.name),
+                description: it.description.trim().to_owned(),
+                operations: operations.remove(&it.name).unwrap_or_default(),
+            })
+            .collect();
+        tags.extend(operations.into_iter().map(|(name, operations)| TagSection {
+            slug: tag_slug(&name),
+            description: String::new(),
+            name, operations,
+        }));
+
+        let schemas = components.map_or_else(Vec::new, |it| {
+            it.schemas.iter()
+                .map(|(name, schema)| {
+                    let resolved = schema.resolve(components);
+                    SchemaView {
+                        name: name.clone(),
+                        anchor: schema_anchor(name),
+                        description: resolved.map(|it| it.description.trim().to_owned()).unwrap_or_default(),
+                        type_label: type_label(schema),
+                        nodes: match schema {
+                            RefOr::Object(ref it) => schema_nodes(it, 0),
+                            RefOr::Ref { .. } => vec![],
+                        },
+                        enum_values: resolved.map_or_else(Vec::new, |it| it.schema_enum.iter().map(|it| it.to_string()).collect()),
+                        example: serde_json::to_string_pretty(&example_value(schema, components, Direction::Schema)).unwrap(),
+                    }
+                })
+                .collect()
+        });
+
+        let site = SiteContext {
+            title: spec.info.title.clone(),
+            servers: spec.servers.iter()
+                .map(|it| ServerView {
+                    url: it.url.clone(),
+                    description: it.description.clone().unwrap_or_default(),
+                })
+                .collect(),
+            tags, schemas,
+        };
+
+        util::write_templates(&templates, &vec![Site::Index(site), Site::Style(AssetContext {}), Site::Script(AssetContext {})], None).unwrap();
+    }
+}
+
+fn generate_operation(
+    path: &str,
+    method: &str,
+    spec: &OperationSpec,
+    security: &[specs::SecurityRequirementSpec],
+    components: Option<&ComponentsSpec>,
+) -> OperationView {
+    let params = spec.parameters.iter()
+        .map(|param| ParamView {
+            name: param.name.clone(),
+            location: param.location.clone(),
+            type_label: type_label(&param.schema),
+            required: param.required || param.location == "path",
+            deprecated: param.deprecated,
+            description: param.description.as_ref().map(|it| it.trim().to_owned()).unwrap_or_default(),
+            enum_values: param.schema.resolve(components)
+                .map_or_else(Vec::new, |it| it.schema_enum.iter().map(|it| it.to_string()).collect()),
+        })
+        .collect();
+
+    let body = spec.request_body.as_ref()
+        .and_then(|it| it.resolve(components))
+        .map(|body| BodyView {
+            required: body.required,
+            description: body.description.as_ref().map(|it| it.trim().to_owned()).unwrap_or_default(),
+            content: describe_content(&body.content, components, Direction::Request),
+        });
+
+    let responses = spec.responses.iter()
+        .map(|(status, response)| {
+            let resolved = response.resolve(components);
+            ResponseView {
+                status: status.to_uppercase(),
+                class: match status.chars().next() {
+                    Some(it @ '1'..='5') => format!("status-{}xx", it),
+                    _ => String::from("status-default"),
+                },
+                reason: reason(status).unwrap_or_default(),
+                description: resolved
+                    .and_then(|it| it.description.as_ref())
+                    .map(|it| it.trim().to_owned())
+                    .unwrap_or_default(),
+                content: resolved.and_then(|it| describe_content(&it.content, components, Direction::Response)),
+            }
+        })
+        .collect();
+
+    let title = spec.summary.as_ref()
+        .map(|it| it.trim().to_owned())
+        .filter(|it| !it.is_empty())
+        .unwrap_or_else(|| spec.operation_id.clone());
+
+    OperationView {
+        anchor: format!("op-{}", case::kebab_case(&spec.operation_id)),
+        search: format!("{} {} {} {}", method, path, spec.operation_id, title).to_lowercase(),
+        operation_id: spec.operation_id.clone(),
+        method: method.to_uppercase(),
+        method_class: method.to_lowercase(),
+        path: String::from(path),
+        deprecated: spec.deprecated,
+        security: spec.security.as_ref().map_or(security, |it| it.as_slice())
+            .iter()
+            .filter(|it| !it.is_empty())
+            .map(|it| it.iter()
+                .map(|(scheme, scopes)| match scopes.is_empty() {
+                    true => scheme.clone(),
+                    false => format!("{} ({})", scheme, scopes.join(", ")),
+                })
+                .collect::<Vec<_>>()
+                .join(" and "))
+            .collect(),
+        title, params, body, responses,
+    }
+}
+
+/// The first media type of a body, preferring json, with the tree of its
+/// schema and an example.
+fn describe_content(
+    content: &BTreeMap<String, specs::MediaTypeSpec>,
+    components: Option<&ComponentsSpec>,
+    direction: Direction,
+) -> Option<ContentView> {
+    let (media_type, media) = content.iter()
+        .find(|(it, _)| specs::is_json_media_type(it))
+        .or_else(|| content.iter().next())?;
+
+    Some(ContentView {
+        media_type: media_type.clone(),
+        type_label: media.schema.as_ref().map(type_label).unwrap_or_default(),
+        nodes: match media.schema {
+            Some(RefOr::Object(ref it)) => schema_nodes(it, 0),
+            _ => vec![],
+        },
+        example: media.schema.as_ref()
+            .filter(|_| specs::is_json_media_type(media_type))
+            .map(|it| serde_json::to_string_pretty(&example_value(it, components, direction)).unwrap()),
+    })
+}
+
+/// Describes the type of a schema in a line of html, references link to the
+/// sections of their schemas.
+fn type_label(spec: &RefOr<SchemaSpec>) -> String {
+    let spec = match spec {
+        RefOr::Ref { ref ref_path } => return schema_link(ref_path),
+        RefOr::Object(ref it) => it,
+    };
+
+    let label = if !spec.all_of.is_empty() {
+        format!("all of {}", labels(&spec.all_of))
+    } else if !spec.one_of.is_empty() {
+        format!("one of {}", labels(&spec.one_of))
+    } else if !spec.any_of.is_empty() {
+        format!("any of {}", labels(&spec.any_of))
+    } else {
+        match spec.schema_type.as_deref() {
+            Some("array") => match spec.items {
+                Some(ref it) => format!("array of {}", type_label(&it.map_cloned(|it| RefOr::Object(*it)))),
+                None => String::from("array"),
+            },
+            Some("object") | None if spec.properties.is_empty() && spec.additional_properties.is_some() => {
+                match spec.additional_properties {
+                    Some(AdditionalPropertiesSpec::Schema(ref it)) => format!("map of {}", type_label(it)),
+                    _ => String::from("map"),
+                }
+            },
+            Some(it) => match spec.format {
+                Some(ref format) => format!("{} ({})", escape(it), escape(format)),
+                None => escape(it),
+            },
+            None if !spec.properties.is_empty() => String::from("object"),
+            None => String::from("any"),
+        }
+    };
+
+    if spec.nullable {
+        format!("{}, nullable", label)
+    } else {
+        label
+    }
+}
+
+fn labels(parts: &[RefOr<SchemaSpec>]) -> String {
+    parts.iter().map(type_label).collect::<Vec<_>>().join(", ")
+}
+
+fn schema_link(ref_path: &str) -> String {
+    match ref_path.strip_prefix(SCHEMA_PREFIX) {
+        Some(name) => format!("<a href=\"#{}\">{}</a>", schema_anchor(name), escape(name)),
+        None => format!("<code>{}</code>", escape(ref_path)),
+    }
+}
+
+/// The tree of an inline schema: its properties, the ones of inline parts
+/// and the variants of unions. Referenced schemas link to their section
+/// instead of being expanded, which also ends cycles.
+fn schema_nodes(spec: &SchemaSpec, depth: usize) -> Vec<SchemaNode> {
+    if depth >= MAX_DEPTH {
+        return vec![];
+    }
+
+    let mut nodes = vec![];
+    for part in spec.all_of.iter() {
+        if let RefOr::Object(ref it) = part {
+            nodes.extend(schema_nodes(it, depth));
+        }
+    }
+
+    let variants = if spec.one_of.is_empty() { &spec.any_of } else { &spec.one_of };
+    for (i, variant) in variants.iter().enumerate() {
+        nodes.push(SchemaNode {
+            name: format!("variant {}", i + 1),
+            type_label: type_label(variant),
+            required: false,
+            description: String::new(),
+            children: child_nodes(variant, depth),
+        });
+    }
+
+    for (name, property) in spec.properties.iter() {
+        let mut notes = vec![];
+        if let RefOr::Object(ref it) = property {
+            if !it.description.trim().is_empty() {
+                notes.push(it.description.trim().to_owned());
+            }
+            if it.read_only {
+                notes.push(String::from("Read only."));
+            }
+            if it.write_only {
+                notes.push(String::from("Write only."));
+            }
+            if !it.schema_enum.is_empty() {
+                let values: Vec<String> = it.schema_enum.iter().map(|it| it.to_string()).collect();
+                notes.push(format!("One of {}.", values.join(", ")));
+            }
+            if let Some(ref default) = it.default {
+                notes.push(format!("Default: {}.", default));
+            }
+        }
+
+        nodes.push(SchemaNode {
+            name: name.clone(),
+            type_label: type_label(property),
+            required: spec.required.contains(name),
+            description: notes.join(" "),
+            children: child_nodes(property, depth),
+        });
+    }
+
+    nodes
+}
+
+fn child_nodes(spec: &RefOr<SchemaSpec>, depth: usize) -> Vec<SchemaNode> {
+    let spec = match spec {
+        RefOr::Object(ref it) => it,
+        RefOr::Ref { .. } => return vec![],
+    };
+
+    match spec.items {
+        Some(RefOr::Object(ref items)) => schema_nodes(items, depth + 1),
+        Some(RefOr::Ref { .. }) => vec![],
+        None => schema_nodes(spec, depth + 1),
+    }
+}
+
+fn schema_anchor(name: &str) -> String {
+    let name: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("schema-{}", name)
+}
+
+/// Escapes text that is put into html by the generator rather than the
+/// templates, which use the escape filter.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ServerView {
+    url: String,
+    description: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ParamView {
+    name: String,
+    location: String,
+    /// The type as html
+    type_label: String,
+    required: bool,
+    deprecated: bool,
+    description: String,
+    /// The allowed values as json
+    enum_values: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SchemaNode {
+    name: String,
+    /// The type as html
+    type_label: String,
+    required: bool,
+    description: String,
+    children: Vec<SchemaNode>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ContentView {
+    media_type: String,
+    /// The type as html
+    type_label: String,
+    /// The tree of inline schemas, referenced ones are in the schemas section
+    nodes: Vec<SchemaNode>,
+    /// A pretty printed json example
+    example: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct BodyView {
+    required: bool,
+    description: String,
+    content: Option<ContentView>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ResponseView {
+    status: String,
+    /// The css class of the status range
+    class: String,
+    reason: String,
+    description: String,
+    content: Option<ContentView>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct OperationView {
+    anchor: String,
+    /// The summary, or the operation id if there is none
+    title: String,
+    /// Lowercase text the search matches against
+    search: String,
+    operation_id: String,
+    method: String,
+    method_class: String,
+    path: String,
+    deprecated: bool,
+    /// The requirements, any of which grants access
+    security: Vec<String>,
+    params: Vec<ParamView>,
+    body: Option<BodyView>,
+    responses: Vec<ResponseView>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct TagSection {
+    name: String,
+    slug: String,
+    description: String,
+    operations: Vec<OperationView>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SchemaView {
+    name: String,
+    anchor: String,
+    description: String,
+    /// The type as html
+    type_label: String,
+    nodes: Vec<SchemaNode>,
+    /// The allowed values as json
+    enum_values: Vec<String>,
+    /// A pretty printed json example
+    example: String,
+}
+
+#[derive(Serialize)]
+struct SiteContext {
+    title: String,
+    servers: Vec<ServerView>,
+    tags: Vec<TagSection>,
+    schemas: Vec<SchemaView>,
+}
+
+/// Context of the assets, which don't depend on the spec
+#[derive(Serialize)]
+struct AssetContext {}
+
+/// The files of the site.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Site {
+    Index(SiteContext),
+    Style(AssetContext),
+    Script(AssetContext),
+}
+
+impl TemplateContext for Site {
+    fn template(&self) -> &'static str {
+        match self {
+            Site::Index(_) => "index.tera",
+            Site::Style(_) => "style.tera",
+            Site::Script(_) => "script.tera",
+        }
+    }
+
+    fn filename(&self) -> String {
+        match self {
+            Site::Index(_) => String::from("index.html"),
+            Site::Style(_) => String::from("style.css"),
+            Site::Script(_) => String::from("script.js"),
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use serde_derive::Serialize;
+use serde_json::{json, Map, Value};
+use crate::{
+    specs::{OpenApiSpec, SchemaSpec, RefOr, AdditionalPropertiesSpec},
+    gen::{Generator, Options, TemplateContext},
+    util,
+};
+use super::models::SCHEMA_PREFIX;
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Exports every component schema as a standalone JSON Schema (draft
+/// 2020-12) file, references to other schemas point to their files.
+pub struct JsonSchemaGenerator;
+impl Generator for JsonSchemaGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates("json-schema").unwrap();
+        let schemas = match spec.components {
+            Some(ref it) => &it.schemas,
+            None => {
+                println!("the spec has no component schemas to export");
+                return;
+            },
+        };
+
+        let files: Vec<SchemaFile> = schemas.iter()
+            .map(|(name, schema)| {
+                let mut root = Map::new();
+                root.insert(String::from("$schema"), Value::from(DIALECT));
+                root.insert(String::from("$id"), Value::from(file_name(name)));
+                root.insert(String::from("title"), Value::from(name.as_str()));
+
+                // validators would follow the reference forever
+                if is_ref_cycle(name, schemas) {
+                    println!(
+                        "\t{}{} only references itself, it is exported as allowing anything",
+                        SCHEMA_PREFIX, name,
+                    );
+                } else {
+                    match convert(schema) {
+                        Value::Object(it) => root.extend(it),
+                        other => { root.insert(String::from("allOf"), json!([other])); },
+                    }
+                }
+
+                SchemaFile {
+                    file_name: file_name(name),
+                    schema: serde_json::to_string_pretty(&root).unwrap(),
+                }
+            })
+            .collect();
+
+        util::write_templates(&templates, &files, None).unwrap();
+    }
+}
+
+/// Whether a schema is a reference to itself, directly or through other
+/// schemas that are nothing but references.
+fn is_ref_cycle(name: &str, schemas: &BTreeMap<String, RefOr<SchemaSpec>>) -> bool {
+    let mut visited = vec![name];
+    let mut current = &schemas[name];
+    while let RefOr::Ref { ref ref_path } = current {
+        let target = match ref_path.strip_prefix(SCHEMA_PREFIX) {
+            Some(it) => it,
+            None => return false,
+        };
+        if target == name {
+            return true;
+        }
+        // a cycle further down is reported for the schemas that are part of it
+        if visited.contains(&target) {
+            return false;
+        }
+
+        visited.push(target);
+        current = match schemas.get(target) {
+            Some(it) => it,
+            None => return false,
+        };
+    }
+    false
+}
+
+/// The file of a component schema, names are kept as long as they are safe
+/// to use as file names.
+fn file_name(name: &str) -> String {
+    let name: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    format!("{}.schema.json", name)
+}
+
+/// Rewrites a reference to a component schema to its file, pointers into
+/// the schema keep their fragment since the structure stays the same.
+fn convert_ref(ref_path: &str) -> Value {
+    let target = match ref_path.strip_prefix(SCHEMA_PREFIX) {
+        Some(it) => it,
+        None => {
+            println!("\tcan't export the reference {}, keeping it", ref_path);
+            return json!({ "$ref": ref_path });
+        },
+    };
+
+    let (name, pointer) = match target.find('/') {
+        Some(i) => (&target[..i], &target[i..]),
+        None => (target, ""),
+    };
+    let name = name.replace("~1", "/").replace("~0", "~");
+    let fragment = if pointer.is_empty() { String::new() } else { format!("#{}", pointer) };
+
+    json!({ "$ref": format!("{}{}", file_name(&name), fragment) })
+}
+
+fn convert(spec: &RefOr<SchemaSpec>) -> Value {
+    match spec {
+        RefOr::Ref { ref ref_path } => convert_ref(ref_path),
+        RefOr::Object(ref it) => convert_schema(it),
+    }
+}
+
+fn convert_schema(spec: &SchemaSpec) -> Value {
+    let mut schema = Map::new();
+    let mut insert = |key: &str, value: Value| { schema.insert(String::from(key), value); };
+
+    // null is a type of its own, schemas without a type allow it besides
+    match (&spec.schema_type, spec.nullable) {
+        (Some(it), true) => insert("type", json!([it, "null"])),
+        (Some(it), false) => insert("type", Value::from(it.as_str())),
+        (None, _) => {},
+    }
+
+    if !spec.description.is_empty() {
+        insert("description", Value::from(spec.description.as_str()));
+    }
+    if let Some(ref it) = spec.format {
+        insert("format", Value::from(it.as_str()));
+    }
+    if !spec.schema_enum.is_empty() {
+        let mut values = spec.schema_enum.clone();
+        if spec.nullable && !values.contains(&Value::Null) {
+            values.push(Value::Null);
+        }
+        insert("enum", Value::from(values));
+    }
+    if let Some(ref it) = spec.default {
+        insert("default", it.clone());
+    }
+    if let Some(ref it) = spec.example {
+        insert("examples", json!([it]));
+    }
+    if spec.read_only {
+        insert("readOnly", Value::from(true));
+    }
+    if spec.write_only {
+        insert("writeOnly", Value::from(true));
+    }
+
+    if let Some(ref it) = spec.items {
+        insert("items", convert(&it.map_cloned(|it| RefOr::Object(*it))));
+    }
+    if !spec.properties.is_empty() {
+        let properties: Map<String, Value> = spec.properties.iter()
+            .map(|(name, it)| (name.clone(), convert(it)))
+            .collect();
+        insert("properties", Value::from(properties));
+    }
+    if !spec.required.is_empty() {
+        insert("required", Value::from(spec.required.clone()));
+    }
+    match spec.additional_properties {
+        Some(AdditionalPropertiesSpec::Allowed(it)) => insert("additionalProperties", Value::from(it)),
+        Some(AdditionalPropertiesSpec::Schema(ref it)) => insert("additionalProperties", convert(it)),
+        None => {},
+    }
+    if !spec.pattern_properties.is_empty() {
+        let properties: Map<String, Value> = spec.pattern_properties.iter()
+            .map(|(pattern, it)| (pattern.clone(), convert(it)))
+            .collect();
+        insert("patternProperties", Value::from(properties));
+    }
+    if let Some(ref it) = spec.property_names {
+        insert("propertyNames", convert(it));
+    }
+
+    if let Some(it) = spec.min_length {
+        insert("minLength", Value::from(it));
+    }
+    if let Some(it) = spec.max_length {
+        insert("maxLength", Value::from(it));
+    }
+    if let Some(ref it) = spec.pattern {
+        insert("pattern", Value::from(it.as_str()));
+    }
+    match spec.lower_bound() {
+        Some((it, true)) => insert("exclusiveMinimum", number(it)),
+        Some((it, false)) => insert("minimum", number(it)),
+        None => {},
+    }
+    match spec.upper_bound() {
+        Some((it, true)) => insert("exclusiveMaximum", number(it)),
+        Some((it, false)) => insert("maximum", number(it)),
+        None => {},
+    }
+    if let Some(it) = spec.multiple_of {
+        insert("multipleOf", number(it));
+    }
+    if let Some(it) = spec.min_items {
+        insert("minItems", Value::from(it));
+    }
+    if let Some(it) = spec.max_items {
+        insert("maxItems", Value::from(it));
+    }
+    if spec.unique_items {
+        insert("uniqueItems", Value::from(true));
+    }
+
+    let mut all_of: Vec<Value> = spec.all_of.iter().map(convert).collect();
+    let parts = if spec.one_of.is_empty() { &spec.any_of } else { &spec.one_of };
+    let keyword = if spec.one_of.is_empty() { "anyOf" } else { "oneOf" };
+    match discriminated_variants(spec, parts) {
+        // the discriminator selects the variant a value has to match
+        Some((property, variants)) => {
+            let values: Vec<&str> = variants.iter().map(|(value, _)| value.as_str()).collect();
+            all_of.push(json!({
+                "properties": { property.as_str(): { "enum": values } },
+                "required": [property],
+            }));
+            for (value, ref_path) in variants.iter() {
+                all_of.push(json!({
+                    "if": {
+                        "properties": { property.as_str(): { "const": value } },
+                        "required": [property],
+                    },
+                    "then": convert_ref(ref_path),
+                }));
+            }
+        },
+        None if !parts.is_empty() => insert(keyword, Value::from(parts.iter().map(convert).collect::<Vec<_>>())),
+        None => {},
+    }
+    if !all_of.is_empty() {
+        insert("allOf", Value::from(all_of));
+    }
+
+    // compositions without a type of their own can't list null as one
+    if spec.nullable && spec.schema_type.is_none() && spec.schema_enum.is_empty() && !schema.is_empty() {
+        return json!({ "anyOf": [Value::from(schema), { "type": "null" }] });
+    }
+
+    Value::from(schema)
+}
+
+/// The discriminator property and the value and reference of every variant,
+/// if the variants are all references a discriminator can select.
+fn discriminated_variants(spec: &SchemaSpec, parts: &[RefOr<SchemaSpec>]) -> Option<(String, Vec<(String, String)>)> {
+    let discriminator = spec.discriminator.as_ref()?;
+    if parts.is_empty() {
+        return None;
+    }
+
+    let variants = parts.iter()
+        .map(|part| match part {
+            RefOr::Ref { ref ref_path } => {
+                let schema = ref_path.rsplit('/').next().unwrap_or_default();
+                let value = discriminator.mapping.iter()
+                    .find(|(_, target)| *target == ref_path || target.as_str() == schema)
+                    .map_or_else(|| String::from(schema), |(value, _)| value.clone());
+                Some((value, ref_path.clone()))
+            },
+            RefOr::Object(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some((discriminator.property_name.clone(), variants))
+}
+
+/// Whole numbers are written without a fraction.
+fn number(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        Value::from(value as i64)
+    } else {
+        Value::from(value)
+    }
+}
+
+#[derive(Serialize)]
+struct SchemaFile {
+    file_name: String,
+    /// The schema as pretty printed json
+    schema: String,
+}
+
+impl TemplateContext for SchemaFile {
+    fn template(&self) -> &'static str { "schema.tera" }
+    fn filename(&self) -> String { self.file_name.clone() }
+}
@@ -0,0 +1,328 @@
+use std::collections::{HashMap, HashSet};
+use serde_derive::Serialize;
+use crate::{
+    specs::{OpenApiSpec, SchemaSpec, RefOr, ComponentsSpec},
+    gen::{
+        Generator, Options, TemplateContext,
+        models::{
+            self, Language, schema_type, non_empty, discriminator_tags, all_of_properties,
+            type_name, camel_ident, shouty_snake_ident,
+        },
+    },
+    util,
+};
+
+/// Generates kotlinx.serialization data classes for all schemas, unions with
+/// a discriminator become sealed interfaces their variants implement.
+pub struct KotlinGenerator;
+impl Generator for KotlinGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates("kotlin").unwrap();
+        let package: String = spec.info.title.to_lowercase().chars()
+            .filter(|it| it.is_ascii_alphanumeric())
+            .collect();
+        let package = match package.as_str() {
+            "" => String::from("api"),
+            it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("api{}", it),
+            _ => package,
+        };
+
+        let mut builder = ModelBuilder::new(spec.components.as_ref(), Kotlin::new(spec.components.as_ref()));
+        builder.add_schemas();
+
+        let models = builder.models;
+        let file = ModelsFile {
+            package,
+            uses_json: models.iter().any(|it| it.uses_json()),
+            has_sealed: models.iter().any(|it| matches!(it, Model::Sealed { .. })),
+            models,
+        };
+
+        util::write_templates(&templates, &vec![file], None).unwrap();
+    }
+}
+
+/// The kotlin side of the models. Intersections are flattened into data
+/// classes, which implement the sealed interfaces of the unions they are
+/// variants of.
+struct Kotlin {
+    /// Sealed interfaces of component unions with a discriminator by the type
+    /// name of their variants, with the discriminator property and value
+    parents: HashMap<String, Vec<Parent>>,
+    /// Component unions that are sealed interfaces
+    sealed: HashSet<String>,
+}
+
+#[derive(Clone, Debug)]
+struct Parent {
+    name: String,
+    property: String,
+    value: String,
+}
+
+type ModelBuilder<'a> = models::ModelBuilder<'a, Kotlin>;
+
+impl Kotlin {
+    fn new(components: Option<&ComponentsSpec>) -> Kotlin {
+        let mut parents: HashMap<String, Vec<Parent>> = HashMap::new();
+        let mut sealed = HashSet::new();
+        for (name, spec) in components.into_iter().flat_map(|it| it.schemas.iter()) {
+            if let RefOr::Object(ref spec) = spec {
+                // sealed interfaces need every variant to be a class of its own
+                let tags = match discriminator_tags(spec) {
+                    Some(it) => it,
+                    None => continue,
+                };
+
+                let property = spec.discriminator.as_ref().map(|it| it.property_name.clone()).unwrap_or_default();
+                for (variant, value) in tags {
+                    parents.entry(type_name(&variant)).or_default().push(Parent {
+                        name: type_name(name),
+                        property: property.clone(),
+                        value,
+                    });
+                }
+                sealed.insert(type_name(name));
+            }
+        }
+
+        Kotlin { parents, sealed }
+    }
+}
+
+impl Language for Kotlin {
+    type Model = Model;
+
+    const ANY: &'static str = "JsonElement";
+
+    fn primitive_type(spec: &SchemaSpec) -> String {
+        let kotlin_type = match (schema_type(spec), spec.format.as_deref()) {
+            ("string", _) => "String",
+            ("integer", Some("int32")) => "Int",
+            ("integer", _) => "Long",
+            ("number", Some("float")) => "Float",
+            ("number", _) => "Double",
+            ("boolean", _) => "Boolean",
+            _ => Self::ANY,
+        };
+
+        String::from(kotlin_type)
+    }
+
+    fn array_type(items: &str) -> String {
+        format!("List<{}>", items)
+    }
+
+    fn map_type(values: Option<String>) -> String {
+        format!("Map<String, {}>", values.as_deref().unwrap_or(Self::ANY))
+    }
+
+    fn doc(text: &str) -> Option<String> {
+        // the description would end the kdoc comment
+        non_empty(text).map(|it| it.replace("*/", "* /"))
+    }
+
+    fn alias(name: String, description: Option<String>, alias: String) -> Model {
+        Model::Alias { name, description, alias }
+    }
+
+    /// String enums become enum classes, kotlinx.serialization can't decode
+    /// others by value so they keep their primitive type.
+    fn enum_type(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let values: Vec<&str> = spec.schema_enum.iter()
+            .filter(|it| !it.is_null())
+            .filter_map(|it| it.as_str())
+            .collect();
+        if values.is_empty() || values.len() != spec.schema_enum.iter().filter(|it| !it.is_null()).count() {
+            return Self::primitive_type(spec);
+        }
+
+        let mut variants: Vec<EnumVariant> = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let member = spec.enum_var_names.get(i)
+                .map_or_else(|| member_name(value), |it| member_name(it));
+
+            let mut unique = member.clone();
+            let mut suffix = 1;
+            while variants.iter().any(|it| it.name == unique) {
+                suffix += 1;
+                unique = format!("{}_{}", member, suffix);
+            }
+
+            variants.push(EnumVariant {
+                name: unique,
+                value: string_literal(value),
+                description: spec.enum_descriptions.get(i).filter(|it| !it.is_empty()).cloned(),
+            });
+        }
+
+        builder.models.push(Model::Enum {
+            name: String::from(name),
+            description: Self::doc(&spec.description),
+            variants,
+        });
+
+        String::from(name)
+    }
+
+    /// Data classes can't inherit, so the parts of an intersection are
+    /// flattened into one.
+    fn object(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let parents = builder.language.parents.get(name).cloned().unwrap_or_default();
+        if parents.iter().any(|it| it.value != parents[0].value) {
+            println!("\t{} has different discriminator values, using {}", name, parents[0].value);
+        }
+
+        let (properties, required) = all_of_properties(spec, builder.components);
+        let mut fields = vec![];
+        for (property, property_spec) in properties.iter() {
+            // the serializer writes the discriminator of sealed interfaces
+            if parents.iter().any(|it| it.property == *property) {
+                continue;
+            }
+
+            let property_type = builder.type_of(property_spec, &format!("{}{}", name, type_name(property)));
+            let required = required.contains(property);
+            let nullable = builder.is_nullable(property_spec);
+
+            let ident = property_name(property);
+            fields.push(Field {
+                serial_name: Some(property.as_str()).filter(|it| *it != ident.trim_matches('`')).map(string_literal),
+                name: ident,
+                field_type: if !required || nullable { format!("{}?", property_type) } else { property_type },
+                optional: !required,
+                description: builder.description(property_spec),
+            });
+        }
+
+        builder.models.push(Model::DataClass {
+            name: String::from(name),
+            description: Self::doc(&spec.description),
+            serial_name: parents.first().map(|it| string_literal(&it.value)),
+            parents: parents.iter().map(|it| it.name.clone()).collect(),
+            fields,
+        });
+
+        String::from(name)
+    }
+
+    /// Component unions with a discriminator are sealed interfaces, others
+    /// can't be decoded without a custom serializer and stay json.
+    fn union(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        if !builder.language.sealed.contains(name) {
+            return String::from(Self::ANY);
+        }
+
+        builder.models.push(Model::Sealed {
+            name: String::from(name),
+            description: Self::doc(&spec.description),
+            tag: string_literal(&spec.discriminator.as_ref().map(|it| it.property_name.clone()).unwrap_or_default()),
+        });
+
+        String::from(name)
+    }
+}
+
+/// Quotes a value as string literal, `$` would start a template otherwise.
+fn string_literal(value: &str) -> String {
+    serde_json::Value::from(value).to_string().replace('$', "\\$")
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "break", "class", "continue", "do", "else", "false", "for", "fun", "if", "in",
+    "interface", "is", "null", "object", "package", "return", "super", "this", "throw", "true",
+    "try", "typealias", "typeof", "val", "var", "when", "while",
+];
+
+fn property_name(name: &str) -> String {
+    let ident = camel_ident(name);
+    match ident.as_str() {
+        "" => String::from("value"),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", it),
+        it if KEYWORDS.contains(&it) => format!("`{}`", it),
+        _ => ident,
+    }
+}
+
+fn member_name(value: &str) -> String {
+    let ident = shouty_snake_ident(value);
+    match ident.as_str() {
+        "" if value.starts_with('-') => String::from("MINUS"),
+        "" => String::from("EMPTY"),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("VALUE_{}", it),
+        _ => ident,
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type")]
+enum Model {
+    DataClass {
+        name: String,
+        description: Option<String>,
+        /// The discriminator value as literal if it is a variant of sealed
+        /// interfaces
+        serial_name: Option<String>,
+        /// The sealed interfaces it implements
+        parents: Vec<String>,
+        fields: Vec<Field>,
+    },
+    Enum {
+        name: String,
+        description: Option<String>,
+        variants: Vec<EnumVariant>,
+    },
+    Sealed {
+        name: String,
+        description: Option<String>,
+        /// The discriminator property as literal
+        tag: String,
+    },
+    Alias {
+        name: String,
+        description: Option<String>,
+        alias: String,
+    },
+}
+
+impl Model {
+    fn uses_json(&self) -> bool {
+        match self {
+            Model::DataClass { ref fields, .. } => fields.iter().any(|it| it.field_type.contains("JsonElement")),
+            Model::Alias { ref alias, .. } => alias.contains("JsonElement"),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Field {
+    name: String,
+    /// The json name as literal if it differs from the property name
+    serial_name: Option<String>,
+    field_type: String,
+    /// Whether it defaults to null
+    optional: bool,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct EnumVariant {
+    name: String,
+    /// The value as string literal
+    value: String,
+    description: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ModelsFile {
+    package: String,
+    uses_json: bool,
+    has_sealed: bool,
+    models: Vec<Model>,
+}
+
+impl TemplateContext for ModelsFile {
+    fn template(&self) -> &'static str { "models.tera" }
+    fn filename(&self) -> String { String::from("Models.kt") }
+}
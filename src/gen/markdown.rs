@@ -0,0 +1,561 @@
+use std::collections::{BTreeMap, BTreeSet};
+use serde_derive::Serialize;
+use voca_rs::case;
+use crate::{
+    specs::{
+        self, OpenApiSpec, SchemaSpec, RefOr, AdditionalPropertiesSpec, ComponentsSpec,
+        OperationSpec, ResponseSpec,
+    },
+    gen::{Generator, Options, TemplateContext},
+    util,
+};
+use super::models::SCHEMA_PREFIX;
+use super::docs::{Direction, example_value, reason, tag_slug};
+
+/// Nesting of inline objects that is still listed in property tables
+const MAX_DEPTH: usize = 4;
+
+/// Generates a markdown reference that mdBook can build as is: an index, a
+/// page per tag with its operations and a page per schema.
+pub struct MarkdownGenerator;
+impl Generator for MarkdownGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates("markdown").unwrap();
+        let components = spec.components.as_ref();
+
+        let mut operations: BTreeMap<String, Vec<OperationDoc>> = BTreeMap::new();
+        let mut used_by: BTreeMap<String, BTreeSet<Link>> = BTreeMap::new();
+        for (path, path_spec) in spec.paths.iter() {
+            for (method, op) in path_spec.operations() {
+                let tag = op.tags.first().cloned().unwrap_or_else(|| String::from("default"));
+                let doc = generate_operation(path, method, op, &spec.security, components);
+
+                let link = Link {
+                    label: doc.title.clone(),
+                    href: format!("../tags/{}.md#{}", tag_slug(&tag), doc.anchor),
+                };
+                for schema in operation_references(op, components) {
+                    used_by.entry(schema).or_default().insert(link.clone());
+                }
+
+                operations.entry(tag).or_default().push(doc);
+            }
+        }
+
+        // declared tags keep their order, the ones only operations use follow
+        let mut tags: Vec<(String, String)> = spec.tags.iter()
+            .map(|it| (it.name.clone(), it.description.trim().to_owned()))
+            .collect();
+        for tag in operations.keys() {
+            if !tags.iter().any(|(name, _)| name == tag) {
+                tags.push((tag.clone(), String::new()));
+            }
+        }
+
+        let tag_pages: Vec<TagPage> = tags.into_iter()
+            .map(|(name, description)| TagPage {
+                slug: tag_slug(&name),
+                operations: operations.remove(&name).unwrap_or_default(),
+                name, description,
+            })
+            .collect();
+
+        let schemas = components.map_or_else(BTreeMap::new, |it| it.schemas.clone());
+        for (name, schema) in schemas.iter() {
+            for target in schema_references(schema) {
+                if target != *name {
+                    used_by.entry(target).or_default().insert(Link {
+                        label: name.clone(),
+                        href: schema_file(name),
+                    });
+                }
+            }
+        }
+
+        let schema_pages: Vec<SchemaPage> = schemas.iter()
+            .map(|(name, schema)| generate_schema_page(name, schema, components, &used_by))
+            .collect();
+
+        let index = IndexPage {
+            title: spec.info.title.clone(),
+            servers: spec.servers.iter()
+                .map(|it| Link {
+                    label: it.description.clone().unwrap_or_default(),
+                    href: it.url.clone(),
+                })
+                .collect(),
+            tags: tag_pages.iter()
+                .map(|it| TagLink {
+                    name: it.name.clone(),
+                    slug: it.slug.clone(),
+                    summary: it.description.lines().next().unwrap_or_default().to_owned(),
+                    operations: it.operations.iter()
+                        .map(|op| Link {
+                            label: op.title.clone(),
+                            href: format!("tags/{}.md#{}", it.slug, op.anchor),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            schemas: schemas.keys()
+                .map(|it| Link { label: it.clone(), href: format!("schemas/{}", schema_file(it)) })
+                .collect(),
+        };
+
+        util::write_templates(&templates, &vec![Book::Readme(index.clone()), Book::Summary(index)], None).unwrap();
+        util::write_templates(&templates, &tag_pages, Some("tags")).unwrap();
+        util::write_templates(&templates, &schema_pages, Some("schemas")).unwrap();
+    }
+}
+
+fn generate_operation(
+    path: &str,
+    method: &str,
+    spec: &OperationSpec,
+    security: &[specs::SecurityRequirementSpec],
+    components: Option<&ComponentsSpec>,
+) -> OperationDoc {
+    let params = spec.parameters.iter()
+        .map(|param| {
+            let mut notes = vec![];
+            if param.deprecated {
+                notes.push(String::from("**Deprecated.**"));
+            }
+            if let Some(ref it) = param.description {
+                notes.push(it.trim().to_owned());
+            }
+            if let Some(it) = param.schema.resolve(components).and_then(enum_note) {
+                notes.push(it);
+            }
+
+            ParamDoc {
+                name: param.name.clone(),
+                location: param.location.clone(),
+                type_label: type_label(&param.schema, "../schemas/"),
+                required: param.required || param.location == "path",
+                description: cell(&notes.join(" ")),
+            }
+        })
+        .collect();
+
+    let body = spec.request_body.as_ref()
+        .and_then(|it| it.resolve(components))
+        .map(|body| {
+            let (media_type, content) = describe_content(&body.content, components, Direction::Request);
+            BodyDoc {
+                required: body.required,
+                description: body.description.as_ref().map(|it| it.trim().to_owned()).filter(|it| !it.is_empty()),
+                media_type, content,
+            }
+        });
+
+    let responses = spec.responses.iter()
+        .map(|(status, response)| {
+            let resolved = response.resolve(components);
+            let (media_type, content) = resolved
+                .map_or((None, None), |it| describe_content(&it.content, components, Direction::Response));
+
+            ResponseDoc {
+                status: status.to_uppercase(),
+                reason: reason(status),
+                description: resolved
+                    .and_then(|it| it.description.as_ref())
+                    .map(|it| it.trim().to_owned())
+                    .filter(|it| !it.is_empty()),
+                media_type, content,
+            }
+        })
+        .collect();
+
+    OperationDoc {
+        anchor: case::kebab_case(&spec.operation_id),
+        title: spec.summary.as_ref()
+            .map(|it| it.trim().to_owned())
+            .filter(|it| !it.is_empty())
+            .unwrap_or_else(|| spec.operation_id.clone()),
+        operation_id: spec.operation_id.clone(),
+        method: method.to_uppercase(),
+        path: String::from(path),
+        deprecated: spec.deprecated,
+        security: spec.security.as_ref().map_or(security, |it| it.as_slice())
+            .iter()
+            .filter(|it| !it.is_empty())
+            .map(|it| it.iter()
+                .map(|(scheme, scopes)| match scopes.is_empty() {
+                    true => format!("`{}`", scheme),
+                    false => format!("`{}` ({})", scheme, scopes.join(", ")),
+                })
+                .collect::<Vec<_>>()
+                .join(" and "))
+            .collect(),
+        params, body, responses,
+    }
+}
+
+/// The first media type of a body, preferring json, and its schema.
+fn describe_content(
+    content: &BTreeMap<String, specs::MediaTypeSpec>,
+    components: Option<&ComponentsSpec>,
+    direction: Direction,
+) -> (Option<String>, Option<ContentDoc>) {
+    let (media_type, media) = match content.iter().find(|(it, _)| specs::is_json_media_type(it)).or_else(|| content.iter().next()) {
+        Some(it) => it,
+        None => return (None, None),
+    };
+
+    let doc = media.schema.as_ref().map(|schema| {
+        let example = match specs::is_json_media_type(media_type) {
+            true => Some(serde_json::to_string_pretty(&example_value(schema, components, direction)).unwrap()),
+            false => None,
+        };
+
+        ContentDoc {
+            type_label: type_label(schema, "../schemas/"),
+            rows: match schema {
+                RefOr::Object(ref it) => property_rows(it, "", "../schemas/", 0),
+                RefOr::Ref { .. } => vec![],
+            },
+            example,
+        }
+    });
+
+    (Some(media_type.clone()), doc)
+}
+
+fn generate_schema_page(
+    name: &str,
+    schema: &RefOr<SchemaSpec>,
+    components: Option<&ComponentsSpec>,
+    used_by: &BTreeMap<String, BTreeSet<Link>>,
+) -> SchemaPage {
+    let resolved = schema.resolve(components);
+    let parts = |it: &Vec<RefOr<SchemaSpec>>| it.iter().map(|it| type_label(it, "")).collect::<Vec<_>>();
+
+    SchemaPage {
+        name: String::from(name),
+        file_name: schema_file(name),
+        description: resolved.map(|it| it.description.trim().to_owned()).filter(|it| !it.is_empty()),
+        type_label: type_label(schema, ""),
+        rows: match schema {
+            RefOr::Object(ref it) => property_rows(it, "", "", 0),
+            RefOr::Ref { .. } => vec![],
+        },
+        enum_values: resolved.map_or_else(Vec::new, |it| {
+            it.schema_enum.iter()
+                .zip(it.enum_descriptions.iter().map(Some).chain(std::iter::repeat(None)))
+                .map(|(value, description)| EnumValue {
+                    value: value.to_string(),
+                    description: description.map(|it| cell(it)).unwrap_or_default(),
+                })
+                .collect()
+        }),
+        all_of: resolved.map_or_else(Vec::new, |it| parts(&it.all_of)),
+        one_of: resolved.map_or_else(Vec::new, |it| parts(if it.one_of.is_empty() { &it.any_of } else { &it.one_of })),
+        exclusive: resolved.is_some_and(|it| !it.one_of.is_empty()),
+        discriminator: resolved.and_then(|it| it.discriminator.as_ref()).map(|it| it.property_name.clone()),
+        example: serde_json::to_string_pretty(&example_value(schema, components, Direction::Schema)).unwrap(),
+        used_by: used_by.get(name).map_or_else(Vec::new, |it| it.iter().cloned().collect()),
+    }
+}
+
+/// Describes the type of a schema in a line, references link to the pages
+/// of their schemas relative to the prefix.
+fn type_label(spec: &RefOr<SchemaSpec>, prefix: &str) -> String {
+    let spec = match spec {
+        RefOr::Ref { ref ref_path } => return schema_link(ref_path, prefix),
+        RefOr::Object(ref it) => it,
+    };
+
+    let label = if !spec.all_of.is_empty() {
+        format!("all of {}", labels(&spec.all_of, prefix))
+    } else if !spec.one_of.is_empty() {
+        format!("one of {}", labels(&spec.one_of, prefix))
+    } else if !spec.any_of.is_empty() {
+        format!("any of {}", labels(&spec.any_of, prefix))
+    } else {
+        match spec.schema_type.as_deref() {
+            Some("array") => match spec.items {
+                Some(ref it) => format!("array of {}", type_label(&it.map_cloned(|it| RefOr::Object(*it)), prefix)),
+                None => String::from("array"),
+            },
+            Some("object") | None if spec.properties.is_empty() && spec.additional_properties.is_some() => {
+                match spec.additional_properties {
+                    Some(AdditionalPropertiesSpec::Schema(ref it)) => format!("map of {}", type_label(it, prefix)),
+                    _ => String::from("map"),
+                }
+            },
+            Some(it) => match spec.format {
+                Some(ref format) => format!("{} ({})", it, format),
+                None => String::from(it),
+            },
+            None if !spec.properties.is_empty() => String::from("object"),
+            None => String::from("any"),
+        }
+    };
+
+    if spec.nullable {
+        format!("{}, nullable", label)
+    } else {
+        label
+    }
+}
+
+fn labels(parts: &[RefOr<SchemaSpec>], prefix: &str) -> String {
+    parts.iter().map(|it| type_label(it, prefix)).collect::<Vec<_>>().join(", ")
+}
+
+fn schema_link(ref_path: &str, prefix: &str) -> String {
+    match ref_path.strip_prefix(SCHEMA_PREFIX) {
+        Some(name) => format!("[{}]({}{})", name, prefix, schema_file(name)),
+        None => format!("`{}`", ref_path),
+    }
+}
+
+/// The properties of an object schema, inline objects are listed with their
+/// properties as `parent.child` and the ones of array items as `parent[].child`.
+fn property_rows(spec: &SchemaSpec, path: &str, prefix: &str, depth: usize) -> Vec<PropertyRow> {
+    let mut rows = vec![];
+    for part in spec.all_of.iter() {
+        if let RefOr::Object(ref it) = part {
+            rows.extend(property_rows(it, path, prefix, depth));
+        }
+    }
+
+    for (name, property) in spec.properties.iter() {
+        let mut notes = vec![];
+        let resolved = match property {
+            RefOr::Object(ref it) => Some(it),
+            RefOr::Ref { .. } => None,
+        };
+        if let Some(it) = resolved {
+            if !it.description.trim().is_empty() {
+                notes.push(it.description.trim().to_owned());
+            }
+            if it.read_only {
+                notes.push(String::from("Read only."));
+            }
+            if it.write_only {
+                notes.push(String::from("Write only."));
+            }
+            if let Some(note) = enum_note(it) {
+                notes.push(note);
+            }
+            if let Some(ref default) = it.default {
+                notes.push(format!("Default: `{}`.", default));
+            }
+        }
+
+        let full_name = format!("{}{}", path, name);
+        rows.push(PropertyRow {
+            name: full_name.clone(),
+            type_label: type_label(property, prefix),
+            required: spec.required.contains(name),
+            description: cell(&notes.join(" ")),
+        });
+
+        // nested objects without a page of their own are listed inline
+        if let Some(it) = resolved.filter(|_| depth < MAX_DEPTH) {
+            let items = it.items.as_ref().and_then(|it| match it {
+                RefOr::Object(ref it) => Some(it),
+                RefOr::Ref { .. } => None,
+            });
+            match items {
+                Some(items) => rows.extend(property_rows(items, &format!("{}[].", full_name), prefix, depth + 1)),
+                None => rows.extend(property_rows(it, &format!("{}.", full_name), prefix, depth + 1)),
+            }
+        }
+    }
+
+    rows
+}
+
+fn enum_note(spec: &SchemaSpec) -> Option<String> {
+    if spec.schema_enum.is_empty() {
+        return None;
+    }
+
+    let values: Vec<String> = spec.schema_enum.iter().map(|it| format!("`{}`", it)).collect();
+    Some(format!("One of {}.", values.join(", ")))
+}
+
+/// The schemas an operation references in its parameters and bodies.
+fn operation_references(spec: &OperationSpec, components: Option<&ComponentsSpec>) -> BTreeSet<String> {
+    let mut schemas: Vec<&RefOr<SchemaSpec>> = spec.parameters.iter().map(|it| &it.schema).collect();
+    let body = spec.request_body.as_ref().and_then(|it| it.resolve(components));
+    schemas.extend(body.iter().flat_map(|it| it.content.values()).filter_map(|it| it.schema.as_ref()));
+
+    let responses: Vec<&ResponseSpec> = spec.responses.values().filter_map(|it| it.resolve(components)).collect();
+    schemas.extend(responses.iter().flat_map(|it| it.content.values()).filter_map(|it| it.schema.as_ref()));
+
+    schemas.into_iter().flat_map(schema_references).collect()
+}
+
+fn schema_references(spec: &RefOr<SchemaSpec>) -> BTreeSet<String> {
+    spec.references().into_iter()
+        .filter_map(|it| it.strip_prefix(SCHEMA_PREFIX))
+        .map(|it| it.split('/').next().unwrap_or_default().to_owned())
+        .collect()
+}
+
+fn schema_file(name: &str) -> String {
+    let name: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    format!("{}.md", name)
+}
+
+/// Escapes text for a table cell, which has to stay on one line.
+fn cell(text: &str) -> String {
+    text.trim().replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>")
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+struct Link {
+    label: String,
+    href: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct EnumValue {
+    /// The value as json
+    value: String,
+    description: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ParamDoc {
+    name: String,
+    location: String,
+    type_label: String,
+    required: bool,
+    description: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct PropertyRow {
+    name: String,
+    type_label: String,
+    required: bool,
+    description: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ContentDoc {
+    type_label: String,
+    /// Properties of inline object schemas, referenced ones are on the page
+    /// of the schema
+    rows: Vec<PropertyRow>,
+    /// A pretty printed json example
+    example: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct BodyDoc {
+    required: bool,
+    description: Option<String>,
+    media_type: Option<String>,
+    content: Option<ContentDoc>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ResponseDoc {
+    status: String,
+    reason: Option<String>,
+    description: Option<String>,
+    media_type: Option<String>,
+    content: Option<ContentDoc>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct OperationDoc {
+    anchor: String,
+    /// The summary, or the operation id if there is none
+    title: String,
+    operation_id: String,
+    method: String,
+    path: String,
+    deprecated: bool,
+    /// The requirements, any of which grants access
+    security: Vec<String>,
+    params: Vec<ParamDoc>,
+    body: Option<BodyDoc>,
+    responses: Vec<ResponseDoc>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct TagLink {
+    name: String,
+    slug: String,
+    /// The first line of the description
+    summary: String,
+    operations: Vec<Link>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct IndexPage {
+    title: String,
+    /// The urls of the servers, labeled with their descriptions
+    servers: Vec<Link>,
+    tags: Vec<TagLink>,
+    schemas: Vec<Link>,
+}
+
+/// The pages of the book that are built from the index.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Book {
+    Readme(IndexPage),
+    Summary(IndexPage),
+}
+
+impl TemplateContext for Book {
+    fn template(&self) -> &'static str {
+        match self {
+            Book::Readme(_) => "readme.tera",
+            Book::Summary(_) => "summary.tera",
+        }
+    }
+
+    fn filename(&self) -> String {
+        match self {
+            Book::Readme(_) => String::from("README.md"),
+            Book::Summary(_) => String::from("SUMMARY.md"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TagPage {
+    name: String,
+    slug: String,
+    description: String,
+    operations: Vec<OperationDoc>,
+}
+
+impl TemplateContext for TagPage {
+    fn template(&self) -> &'static str { "tag.tera" }
+    fn filename(&self) -> String { format!("{}.md", self.slug) }
+}
+
+#[derive(Serialize)]
+struct SchemaPage {
+    name: String,
+    file_name: String,
+    description: Option<String>,
+    type_label: String,
+    rows: Vec<PropertyRow>,
+    enum_values: Vec<EnumValue>,
+    all_of: Vec<String>,
+    one_of: Vec<String>,
+    /// Whether exactly one of the variants has to match
+    exclusive: bool,
+    discriminator: Option<String>,
+    example: String,
+    /// Schemas and operations that reference it
+    used_by: Vec<Link>,
+}
+
+impl TemplateContext for SchemaPage {
+    fn template(&self) -> &'static str { "schema.tera" }
+    fn filename(&self) -> String { self.file_name.clone() }
+}
@@ -1,12 +1,92 @@
+use std::collections::BTreeMap;
 use serde::Serialize;
+use serde_derive::Serialize;
 
 use crate::specs::OpenApiSpec;
+mod models;
+mod docs;
 mod typescript;
+mod zod;
+mod rust;
+mod python;
+mod golang;
+mod kotlin;
+mod swift;
+mod json_schema;
+mod markdown;
+mod html;
+mod postman;
 
 pub use typescript::TypescriptGenerator as Typescript;
+pub use typescript::FetchGenerator as Fetch;
+pub use typescript::ReactQueryGenerator as ReactQuery;
+pub use typescript::AxiosGenerator as Axios;
+pub use zod::ZodGenerator as Zod;
+pub use rust::RustClientGenerator as RustClient;
+pub use rust::RustServerGenerator as RustServer;
+pub use python::PythonGenerator as Python;
+pub use golang::GoGenerator as Go;
+pub use kotlin::KotlinGenerator as Kotlin;
+pub use swift::SwiftGenerator as Swift;
+pub use json_schema::JsonSchemaGenerator as JsonSchema;
+pub use markdown::MarkdownGenerator as Markdown;
+pub use html::HtmlGenerator as Html;
+pub use postman::PostmanGenerator as Postman;
 
 pub trait Generator {
-    fn generate(spec: &OpenApiSpec);
+    fn generate(spec: &OpenApiSpec, options: &Options);
+}
+
+/// Switches that change what generators emit, passed on the command line as
+/// `--name` or `--name=value`. Generators ignore the options that don't apply
+/// to them.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Emit an http interceptor that maps error responses to typed errors.
+    pub interceptor: bool,
+
+    /// Emit a helper for every model that fills in the documented defaults.
+    pub with_defaults: bool,
+
+    /// Emit a validation module next to every model file.
+    pub validators: bool,
+
+    /// Emit enums as union types of their values instead of TypeScript enums.
+    pub enum_unions: bool,
+
+    /// Overrides the type a string format is mapped to, given as
+    /// `--format-type=date-time:Date`.
+    pub format_types: BTreeMap<String, String>,
+}
+
+impl Options {
+    pub fn from_args(args: &[String]) -> Options {
+        let mut options = Options::default();
+
+        for arg in args {
+            let mut parts = arg.splitn(2, '=');
+            match (parts.next().unwrap_or(""), parts.next()) {
+                ("--interceptor", None) => options.interceptor = true,
+                ("--with-defaults", None) => options.with_defaults = true,
+                ("--enum-unions", None) => options.enum_unions = true,
+                ("--validators", None) => options.validators = true,
+                ("--format-type", Some(value)) => {
+                    let mut mapping = value.splitn(2, ':');
+                    match (mapping.next(), mapping.next()) {
+                        (Some(format), Some(target)) => {
+                            options.format_types.insert(
+                                String::from(format), String::from(target),
+                            );
+                        },
+                        _ => panic!("expected --format-type=<format>:<type>"),
+                    }
+                },
+                _ => panic!("unknown option: {}", arg),
+            }
+        }
+
+        options
+    }
 }
 
 pub trait TemplateContext : Serialize {
@@ -14,3 +94,14 @@ pub trait TemplateContext : Serialize {
     fn filename(&self) -> String;
 }
 
+/// Re-exports the given modules, using the shared `index.tera`.
+#[derive(Clone, Debug, Serialize)]
+pub struct IndexFile {
+    pub exports: Vec<String>,
+}
+
+impl TemplateContext for IndexFile {
+    fn template(&self) -> &'static str { "index.tera" }
+    fn filename(&self) -> String { String::from("index.ts") }
+}
+
@@ -0,0 +1,483 @@
+use std::collections::{HashMap, HashSet};
+use serde_derive::Serialize;
+use voca_rs::case;
+use crate::{
+    specs::{
+        self, SchemaSpec, RefOr, AdditionalPropertiesSpec, ComponentsSpec, ResponseSpec, DiscriminatorSpec,
+        SecuritySchemeSpec,
+    },
+    util,
+};
+
+pub(super) const SCHEMA_PREFIX: &str = "#/components/schemas/";
+
+/// The part of a model generator that is specific to its language. The
+/// `ModelBuilder` walks the schemas and asks the language for the names of
+/// types and for the models of enums, objects and compositions.
+pub(super) trait Language: Sized {
+    type Model;
+
+    /// The type of values that aren't described any further
+    const ANY: &'static str;
+
+    /// Whether unions are aliases of their variants instead of models of
+    /// their own, so they can't reference themselves either
+    const UNION_ALIASES: bool = false;
+
+    fn primitive_type(spec: &SchemaSpec) -> String;
+
+    fn array_type(items: &str) -> String;
+
+    /// The type of an object without properties, given the type of its
+    /// values if it has any.
+    fn map_type(values: Option<String>) -> String;
+
+    /// The type of a value that may be null. Languages that leave this up to
+    /// the field keep the type as it is.
+    fn nullable_type(value_type: String) -> String {
+        value_type
+    }
+
+    fn type_name(name: &str) -> String {
+        type_name(name)
+    }
+
+    /// The text of a doc comment, `None` if there is none.
+    fn doc(text: &str) -> Option<String> {
+        non_empty(text)
+    }
+
+    fn alias(name: String, description: Option<String>, alias: String) -> Self::Model;
+
+    fn enum_type(builder: &mut ModelBuilder<Self>, spec: &SchemaSpec, name: &str) -> String;
+
+    fn object(builder: &mut ModelBuilder<Self>, spec: &SchemaSpec, name: &str) -> String;
+
+    /// Intersections are flattened into one object unless the language can
+    /// express them otherwise.
+    fn all_of(builder: &mut ModelBuilder<Self>, spec: &SchemaSpec, name: &str) -> String {
+        Self::object(builder, spec, name)
+    }
+
+    fn union(builder: &mut ModelBuilder<Self>, spec: &SchemaSpec, name: &str) -> String;
+}
+
+/// Collects the models of all schemas, inline schemas become models named
+/// after the place they are defined in.
+pub(super) struct ModelBuilder<'a, L: Language> {
+    pub(super) components: Option<&'a ComponentsSpec>,
+    pub(super) models: Vec<L::Model>,
+    /// Schemas that are part of a cycle, which most languages can only
+    /// declare with an indirection in between
+    pub(super) cyclic: HashSet<String>,
+    /// Schemas that are aliases of themselves, possibly through others,
+    /// which can't be declared at all
+    alias_cycles: HashSet<String>,
+    /// Types of the bodies of component responses, which are generated once
+    responses: HashMap<String, Option<String>>,
+    pub(super) language: L,
+}
+
+impl<'a, L: Language> ModelBuilder<'a, L> {
+    pub(super) fn new(components: Option<&'a ComponentsSpec>, language: L) -> ModelBuilder<'a, L> {
+        let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+        let mut alias_edges: HashMap<String, Vec<String>> = HashMap::new();
+        for (name, spec) in components.into_iter().flat_map(|it| it.schemas.iter()) {
+            edges.insert(name.clone(), schema_names(spec.references()));
+            alias_edges.insert(name.clone(), schema_names(alias_refs(spec, L::UNION_ALIASES)));
+        }
+
+        ModelBuilder {
+            components,
+            models: vec![],
+            cyclic: util::cyclic_nodes(&edges),
+            alias_cycles: util::cyclic_nodes(&alias_edges),
+            responses: HashMap::new(),
+            language,
+        }
+    }
+
+    pub(super) fn add_schemas(&mut self) {
+        let schemas = match self.components {
+            Some(it) => &it.schemas,
+            None => return,
+        };
+
+        for (name, spec) in schemas.iter() {
+            let type_name = L::type_name(name);
+            let declared_type = if self.alias_cycles.contains(name) {
+                println!(
+                    "{}{} references itself without an object in between, \
+                    it is generated as {}",
+                    SCHEMA_PREFIX, name, L::ANY,
+                );
+                String::from(L::ANY)
+            } else {
+                // nullability is up to the places the schema is used in
+                self.value_type(spec, &type_name)
+            };
+
+            // schemas that aren't models on their own still need a name
+            if declared_type != type_name {
+                let description = self.description(spec);
+                self.models.push(L::alias(type_name, description, declared_type));
+            }
+        }
+    }
+
+    /// Determines the type of a schema, adding models for enums, objects and
+    /// compositions. Nullable schemas and references to them are passed to
+    /// the language to make them nullable.
+    pub(super) fn type_of(&mut self, spec: &RefOr<SchemaSpec>, name: &str) -> String {
+        let value_type = self.value_type(spec, name);
+        if self.is_nullable(spec) {
+            L::nullable_type(value_type)
+        } else {
+            value_type
+        }
+    }
+
+    fn value_type(&mut self, spec: &RefOr<SchemaSpec>, name: &str) -> String {
+        let spec = match spec {
+            RefOr::Ref { ref ref_path } => return self.ref_type(ref_path),
+            RefOr::Object(ref it) => it,
+        };
+
+        if !spec.schema_enum.is_empty() {
+            L::enum_type(self, spec, name)
+        } else if !spec.all_of.is_empty() {
+            L::all_of(self, spec, name)
+        } else if !spec.one_of.is_empty() || !spec.any_of.is_empty() {
+            L::union(self, spec, name)
+        } else {
+            match schema_type(spec) {
+                "string" | "integer" | "number" | "boolean" => L::primitive_type(spec),
+                "array" => {
+                    let items = match spec.items {
+                        Some(ref it) => self.type_of(&it.map_cloned(|it| RefOr::Object(*it)), &format!("{}Item", name)),
+                        None => String::from(L::ANY),
+                    };
+                    L::array_type(&items)
+                },
+                "object" if !spec.properties.is_empty() => L::object(self, spec, name),
+                "object" => {
+                    let values = self.additional_type(spec, name);
+                    L::map_type(values)
+                },
+                _ => String::from(L::ANY),
+            }
+        }
+    }
+
+    pub(super) fn ref_type(&self, ref_path: &str) -> String {
+        match ref_path.strip_prefix(SCHEMA_PREFIX) {
+            Some(name) => L::type_name(name),
+            None => {
+                println!("\tcould not resolve {}, using {}", ref_path, L::ANY);
+                String::from(L::ANY)
+            },
+        }
+    }
+
+    /// Whether a schema allows null, references are resolved.
+    pub(super) fn is_nullable(&self, spec: &RefOr<SchemaSpec>) -> bool {
+        spec.resolve(self.components)
+            .is_some_and(|it| it.nullable || it.schema_enum.iter().any(|it| it.is_null()))
+    }
+
+    /// Whether a schema is a reference to one that is part of a cycle.
+    pub(super) fn is_cyclic_ref(&self, spec: &RefOr<SchemaSpec>) -> bool {
+        match spec {
+            RefOr::Ref { ref ref_path } => ref_path.strip_prefix(SCHEMA_PREFIX)
+                .is_some_and(|it| self.cyclic.contains(it)),
+            RefOr::Object(_) => false,
+        }
+    }
+
+    /// The type of additional properties, pattern properties are included
+    /// since a map can't tell them apart.
+    pub(super) fn additional_type(&mut self, spec: &SchemaSpec, name: &str) -> Option<String> {
+        let mut types = vec![];
+        match spec.additional_properties {
+            Some(AdditionalPropertiesSpec::Schema(ref it)) => {
+                types.push(self.type_of(it, &format!("{}Value", name)));
+            },
+            Some(AdditionalPropertiesSpec::Allowed(true)) => types.push(String::from(L::ANY)),
+            _ => {},
+        }
+        for (i, pattern) in spec.pattern_properties.values().enumerate() {
+            types.push(self.type_of(pattern, &format!("{}Value{}", name, i + 1)));
+        }
+
+        types.dedup();
+        match types.len() {
+            0 => None,
+            1 => types.pop(),
+            _ => Some(String::from(L::ANY)),
+        }
+    }
+
+    /// The type of the json body of a response, `None` if it has none.
+    /// Inline schemas of component responses are named after the response.
+    pub(super) fn response_type(&mut self, response: &RefOr<ResponseSpec>, name: &str) -> Option<String> {
+        if let RefOr::Ref { ref ref_path } = response {
+            if let Some(cached) = self.responses.get(ref_path) {
+                return cached.clone();
+            }
+        }
+
+        let body = response.resolve(self.components)
+            .and_then(|it| specs::json_schema(&it.content));
+        let body_type = match (response, body) {
+            (_, None) => None,
+            (RefOr::Ref { ref ref_path }, Some(schema)) => {
+                let name = L::type_name(ref_path.rsplit('/').next().unwrap_or_default());
+                Some(self.type_of(&schema, &name))
+            },
+            (_, Some(schema)) => Some(self.type_of(&schema, name)),
+        };
+
+        if let RefOr::Ref { ref ref_path } = response {
+            self.responses.insert(ref_path.clone(), body_type.clone());
+        }
+
+        body_type
+    }
+
+    pub(super) fn description(&self, spec: &RefOr<SchemaSpec>) -> Option<String> {
+        match spec {
+            RefOr::Object(ref it) => L::doc(&it.description),
+            _ => None,
+        }
+    }
+}
+
+/// The names of the component schemas among the given references.
+fn schema_names<S: AsRef<str>>(refs: Vec<S>) -> Vec<String> {
+    refs.iter()
+        .filter_map(|it| it.as_ref().strip_prefix(SCHEMA_PREFIX))
+        .map(String::from)
+        .collect()
+}
+
+/// The references that end up in the alias a schema is declared as. Objects,
+/// enums and compositions are models of their own, which may reference
+/// themselves, aliases can't.
+fn alias_refs(spec: &RefOr<SchemaSpec>, union_aliases: bool) -> Vec<String> {
+    let spec = match spec {
+        RefOr::Ref { ref ref_path } => return vec![ref_path.clone()],
+        RefOr::Object(ref it) => it,
+    };
+
+    if !spec.schema_enum.is_empty() || !spec.all_of.is_empty() {
+        return vec![];
+    }
+
+    if !spec.one_of.is_empty() || !spec.any_of.is_empty() {
+        if !union_aliases {
+            return vec![];
+        }
+        return spec.one_of.iter()
+            .chain(spec.any_of.iter())
+            .flat_map(|it| alias_refs(it, union_aliases))
+            .collect();
+    }
+
+    match schema_type(spec) {
+        "array" => spec.items.as_ref().map_or_else(Vec::new, |it| {
+            alias_refs(&it.map_cloned(|it| RefOr::Object(*it)), union_aliases)
+        }),
+        "object" if spec.properties.is_empty() => {
+            let mut refs = vec![];
+            if let Some(AdditionalPropertiesSpec::Schema(ref value)) = spec.additional_properties {
+                refs.extend(alias_refs(value, union_aliases));
+            }
+            for value in spec.pattern_properties.values() {
+                refs.extend(alias_refs(value, union_aliases));
+            }
+            refs
+        },
+        _ => vec![],
+    }
+}
+
+/// Schemas without a type are objects or arrays if they have the respective
+/// keywords and can be anything otherwise.
+pub(super) fn schema_type(spec: &SchemaSpec) -> &str {
+    match spec.schema_type {
+        Some(ref it) => it.as_str(),
+        None if !spec.properties.is_empty() || spec.additional_properties.is_some()
+            || !spec.pattern_properties.is_empty() => "object",
+        None if spec.items.is_some() => "array",
+        None => "unknown",
+    }
+}
+
+pub(super) fn non_empty(value: &str) -> Option<String> {
+    Some(String::from(value.trim())).filter(|it| !it.is_empty())
+}
+
+/// The discriminator value of the variant a reference points to, which is
+/// the schema name unless the mapping says otherwise.
+pub(super) fn discriminator_value(discriminator: &DiscriminatorSpec, ref_path: &str) -> String {
+    let schema = ref_path.rsplit('/').next().unwrap_or_default();
+    discriminator.mapping.iter()
+        .find(|(_, target)| target.as_str() == ref_path || target.as_str() == schema)
+        .map_or_else(|| String::from(schema), |(value, _)| value.clone())
+}
+
+/// The discriminator values of the variants of a union by their schema
+/// names, `None` unless it has a discriminator and only referenced variants.
+pub(super) fn discriminator_tags(spec: &SchemaSpec) -> Option<Vec<(String, String)>> {
+    let discriminator = spec.discriminator.as_ref()?;
+    let parts = if spec.one_of.is_empty() { &spec.any_of } else { &spec.one_of };
+    if parts.is_empty() {
+        return None;
+    }
+
+    parts.iter()
+        .map(|part| match part {
+            RefOr::Ref { ref ref_path } => {
+                let schema = ref_path.strip_prefix(SCHEMA_PREFIX)?;
+                Some((String::from(schema), discriminator_value(discriminator, ref_path)))
+            },
+            RefOr::Object(_) => None,
+        })
+        .collect()
+}
+
+/// The properties of a schema and the parts of its intersection, with the
+/// names of the required ones. Referenced parts are resolved, the first
+/// declaration of a property keeps its position.
+pub(super) fn all_of_properties(
+    spec: &SchemaSpec,
+    components: Option<&ComponentsSpec>,
+) -> (Vec<(String, RefOr<SchemaSpec>)>, HashSet<String>) {
+    let mut properties: Vec<(String, RefOr<SchemaSpec>)> = vec![];
+    let mut required = HashSet::new();
+    add_properties(spec, components, &mut properties, &mut required, &mut HashSet::new());
+    (properties, required)
+}
+
+fn add_properties(
+    spec: &SchemaSpec,
+    components: Option<&ComponentsSpec>,
+    properties: &mut Vec<(String, RefOr<SchemaSpec>)>,
+    required: &mut HashSet<String>,
+    visited: &mut HashSet<String>,
+) {
+    for part in spec.all_of.iter() {
+        if let RefOr::Ref { ref ref_path } = part {
+            if !visited.insert(ref_path.clone()) {
+                continue;
+            }
+        }
+
+        match part.resolve(components) {
+            Some(it) => add_properties(it, components, properties, required, visited),
+            None => println!("\tcould not resolve a part of an intersection, skipping it"),
+        }
+    }
+
+    for (name, property) in spec.properties.iter() {
+        match properties.iter_mut().find(|(it, _)| it == name) {
+            Some(existing) => existing.1 = property.clone(),
+            None => properties.push((name.clone(), property.clone())),
+        }
+    }
+    required.extend(spec.required.iter().cloned());
+}
+
+/// Replaces the characters that can't be part of an identifier, like the dot
+/// of `businessUnit.ancestors`, so the case conversions split words there
+/// instead of keeping them.
+fn identifier_words(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+pub(super) fn snake_ident(name: &str) -> String {
+    case::snake_case(&identifier_words(name))
+}
+
+pub(super) fn camel_ident(name: &str) -> String {
+    case::camel_case(&identifier_words(name))
+}
+
+pub(super) fn pascal_ident(name: &str) -> String {
+    case::pascal_case(&identifier_words(name))
+}
+
+pub(super) fn shouty_snake_ident(name: &str) -> String {
+    case::shouty_snake_case(&identifier_words(name))
+}
+
+pub(super) fn type_name(name: &str) -> String {
+    let ident = pascal_ident(name);
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", ident)
+    } else {
+        ident
+    }
+}
+
+/// A security scheme as the clients that authorize requests themselves see
+/// it.
+#[derive(Clone, Debug, Serialize)]
+pub(super) struct SecurityScheme {
+    pub(super) name: String,
+    /// The name of the credential in the client
+    pub(super) field: String,
+    /// One of api_key_header, api_key_query, api_key_cookie, basic, bearer
+    /// and http
+    pub(super) kind: String,
+    /// Name of the api key or scheme of other http authorizations
+    pub(super) param_name: String,
+    pub(super) description: Option<String>,
+}
+
+/// Collects the security schemes of the components, `field` names the
+/// credential of a scheme in the client.
+pub(super) fn security_schemes(
+    components: &ComponentsSpec,
+    field: fn(&str) -> String,
+) -> Vec<SecurityScheme> {
+    components.security_schemes.iter()
+        .filter_map(|(name, spec)| {
+            let spec = match spec.resolve(Some(components)) {
+                Some(it) => it,
+                None => {
+                    println!("skipping security scheme {}, could not resolve it", name);
+                    return None;
+                },
+            };
+
+            let (kind, param_name, description) = match spec {
+                SecuritySchemeSpec::ApiKey { name: param_name, location, description } => {
+                    (format!("api_key_{}", location), param_name.clone(), description)
+                },
+                SecuritySchemeSpec::Http { scheme, description, .. } => {
+                    match scheme.to_lowercase().as_str() {
+                        "basic" => (String::from("basic"), String::new(), description),
+                        "bearer" => (String::from("bearer"), String::new(), description),
+                        _ => (String::from("http"), scheme.clone(), description),
+                    }
+                },
+                // the clients only send the access token, obtaining it is
+                // up to the application
+                SecuritySchemeSpec::OAuth2 { description, .. } |
+                SecuritySchemeSpec::OpenIdConnect { description, .. } => {
+                    (String::from("bearer"), String::new(), description)
+                },
+            };
+
+            Some(SecurityScheme {
+                name: name.clone(),
+                field: field(name),
+                kind, param_name,
+                description: description.clone(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+use serde_derive::Serialize;
+use serde_json::{json, Map, Value};
+use voca_rs::case;
+use crate::{
+    specs::{
+        self, OpenApiSpec, SchemaSpec, RefOr, ComponentsSpec, OperationSpec, ServerSpec,
+        SecuritySchemeSpec,
+    },
+    gen::{Generator, Options, TemplateContext},
+    util,
+};
+use super::docs::{Direction, example_value};
+use super::models::camel_ident;
+
+const COLLECTION_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Exports a Postman collection (v2.1) with a folder per tag and a request
+/// per operation, along with an environment per server. Params are filled
+/// in from collection variables named after the operation, e.g.
+/// `getItem.id`, that default to example values.
+pub struct PostmanGenerator;
+impl Generator for PostmanGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates("postman").unwrap();
+        let components = spec.components.as_ref();
+        let slug = match case::kebab_case(&spec.info.title).as_str() {
+            "" => String::from("api"),
+            it => String::from(it),
+        };
+
+        let mut variables = Variables::default();
+        let servers = match spec.servers.is_empty() {
+            true => vec![ServerSpec::root()],
+            false => spec.servers.clone(),
+        };
+        // the collection works on its own with the first server, the
+        // environments switch between all of them
+        variables.insert("baseUrl", &base_url(&servers[0]));
+        for (name, variable) in servers[0].variables.iter() {
+            variables.insert(name, &variable.default);
+        }
+
+        let mut folders: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+        for (path, path_spec) in spec.paths.iter() {
+            for (method, op) in path_spec.operations() {
+                let tag = op.tags.first().cloned().unwrap_or_else(|| String::from("default"));
+                let item = generate_request(path, &path_spec.servers, method, op, components, &mut variables);
+                folders.entry(tag).or_default().push(item);
+            }
+        }
+
+        // declared tags keep their order, the ones only operations use follow
+        let mut items: Vec<Value> = spec.tags.iter()
+            .filter_map(|tag| {
+                let requests = folders.remove(&tag.name)?;
+                Some(json!({
+                    "name": tag.name,
+                    "description": tag.description.trim(),
+                    "item": requests,
+                }))
+            })
+            .collect();
+        items.extend(folders.into_iter().map(|(name, requests)| json!({ "name": name, "item": requests })));
+
+        let mut collection = Map::new();
+        collection.insert(String::from("info"), json!({
+            "name": spec.info.title,
+            "schema": COLLECTION_SCHEMA,
+        }));
+        collection.insert(String::from("item"), Value::from(items));
+        if let Some(auth) = spec.security.first().and_then(|it| auth(it, components, &mut variables)) {
+            collection.insert(String::from("auth"), auth);
+        }
+        collection.insert(String::from("variable"), variables.into_value());
+
+        let mut files = vec![PostmanFile {
+            file_name: format!("{}.postman_collection.json", slug),
+            document: serde_json::to_string_pretty(&collection).unwrap(),
+        }];
+        for (i, server) in servers.iter().enumerate() {
+            let name = server.description.as_ref()
+                .map(|it| it.trim().to_owned())
+                .filter(|it| !it.is_empty())
+                .unwrap_or_else(|| server.url.clone());
+            let file_name = match servers.len() {
+                1 => format!("{}.postman_environment.json", slug),
+                _ => format!("{}-{}.postman_environment.json", slug, environment_slug(&name, i)),
+            };
+
+            let mut values = vec![environment_value("baseUrl", &base_url(server))];
+            values.extend(server.variables.iter().map(|(name, it)| environment_value(name, &it.default)));
+            let environment = json!({
+                "name": format!("{} - {}", spec.info.title, name),
+                "values": values,
+                "_postman_variable_scope": "environment",
+            });
+
+            files.push(PostmanFile {
+                file_name,
+                document: serde_json::to_string_pretty(&environment).unwrap(),
+            });
+        }
+
+        util::write_templates(&templates, &files, None).unwrap();
+    }
+}
+
+fn generate_request(
+    path: &str,
+    path_servers: &[ServerSpec],
+    method: &str,
+    spec: &OperationSpec,
+    components: Option<&ComponentsSpec>,
+    variables: &mut Variables,
+) -> Value {
+    let mut path_variables = vec![];
+    let mut query = vec![];
+    let mut headers = vec![];
+    let mut cookies = vec![];
+    let prefix = camel_ident(&spec.operation_id);
+    for param in spec.parameters.iter() {
+        let variable = format!("{}.{}", prefix, param.name);
+        variables.insert(&variable, &param_example(&param.schema, components));
+        let description = param.description.as_ref().map_or("", |it| it.trim());
+        let value = format!("{{{{{}}}}}", variable);
+
+        match param.location.as_str() {
+            "path" => path_variables.push(json!({ "key": param.name, "value": value, "description": description })),
+            "query" => query.push(json!({
+                "key": param.name,
+                "value": value,
+                "description": description,
+                "disabled": !param.required,
+            })),
+            "header" => headers.push(json!({
+                "key": param.name,
+                "value": value,
+                "description": description,
+                "disabled": !param.required,
+            })),
+            "cookie" => cookies.push(format!("{}={}", param.name, value)),
+            other => println!("\tignoring the param {} in {}", param.name, other),
+        }
+    }
+    if !cookies.is_empty() {
+        headers.push(json!({ "key": "Cookie", "value": cookies.join("; ") }));
+    }
+
+    // postman marks path variables with a colon instead of braces
+    let segments: Vec<String> = path.split('/')
+        .filter(|it| !it.is_empty())
+        .map(|it| match it.strip_prefix('{').and_then(|it| it.strip_suffix('}')) {
+            Some(name) => format!(":{}", name),
+            None => String::from(it),
+        })
+        .collect();
+    let query_string: Vec<String> = query.iter()
+        .filter(|it| it["disabled"] == Value::Bool(false))
+        .map(|it| format!("{}={}", it["key"].as_str().unwrap_or_default(), it["value"].as_str().unwrap_or_default()))
+        .collect();
+
+    // servers of the path or operation replace the ones of the environments
+    let servers = if spec.servers.is_empty() { path_servers } else { &spec.servers };
+    let host = match servers.first() {
+        Some(server) => {
+            let variable = format!("{}BaseUrl", prefix);
+            variables.insert(&variable, &base_url(server));
+            for (name, it) in server.variables.iter() {
+                variables.insert(name, &it.default);
+            }
+            format!("{{{{{}}}}}", variable)
+        },
+        None => String::from("{{baseUrl}}"),
+    };
+    let mut raw = format!("{}/{}", host, segments.join("/"));
+    if !query_string.is_empty() {
+        raw = format!("{}?{}", raw, query_string.join("&"));
+    }
+
+    let mut request = Map::new();
+    request.insert(String::from("method"), Value::from(method.to_uppercase()));
+
+    let body = spec.request_body.as_ref()
+        .and_then(|it| it.resolve(components))
+        .and_then(|body| {
+            let (media_type, media) = body.content.iter()
+                .find(|(it, _)| specs::is_json_media_type(it))
+                .or_else(|| body.content.iter().next())?;
+            headers.push(json!({ "key": "Content-Type", "value": media_type }));
+            Some(generate_body(media_type, media.schema.as_ref(), components))
+        });
+
+    request.insert(String::from("header"), Value::from(headers));
+    request.insert(String::from("url"), json!({
+        "raw": raw,
+        "host": [host],
+        "path": segments,
+        "query": query,
+        "variable": path_variables,
+    }));
+    if let Some(it) = body {
+        request.insert(String::from("body"), it);
+    }
+    // requirements of their own override the ones of the collection
+    match spec.security {
+        Some(ref it) if it.is_empty() || it.iter().any(|it| it.is_empty()) => {
+            request.insert(String::from("auth"), json!({ "type": "noauth" }));
+        },
+        Some(ref it) => if let Some(auth) = auth(&it[0], components, variables) {
+            request.insert(String::from("auth"), auth);
+        },
+        None => {},
+    }
+
+    let title = spec.summary.as_ref()
+        .map(|it| it.trim().to_owned())
+        .filter(|it| !it.is_empty())
+        .unwrap_or_else(|| spec.operation_id.clone());
+    json!({
+        "name": if spec.deprecated { format!("{} (deprecated)", title) } else { title },
+        "request": request,
+    })
+}
+
+/// The body of a request with an example built from its schema, forms list
+/// the properties of the example as fields.
+fn generate_body(media_type: &str, schema: Option<&RefOr<SchemaSpec>>, components: Option<&ComponentsSpec>) -> Value {
+    let example = schema.map_or(Value::Null, |it| example_value(it, components, Direction::Request));
+    let fields = |file_type: bool| -> Vec<Value> {
+        let properties = match example {
+            Value::Object(ref it) => it.iter().collect(),
+            _ => vec![],
+        };
+        properties.into_iter()
+            .map(|(key, value)| {
+                let binary = file_type && schema
+                    .and_then(|it| it.resolve(components))
+                    .and_then(|it| it.properties.get(key))
+                    .and_then(|it| it.resolve(components))
+                    .is_some_and(|it| it.format.as_deref() == Some("binary"));
+                match binary {
+                    true => json!({ "key": key, "type": "file", "src": [] }),
+                    false => json!({ "key": key, "value": text(value), "type": "text" }),
+                }
+            })
+            .collect()
+    };
+
+    match specs::media_type_essence(media_type).as_str() {
+        "application/x-www-form-urlencoded" => json!({ "mode": "urlencoded", "urlencoded": fields(false) }),
+        "multipart/form-data" => json!({ "mode": "formdata", "formdata": fields(true) }),
+        it if specs::is_json_media_type(it) => json!({
+            "mode": "raw",
+            "raw": serde_json::to_string_pretty(&example).unwrap(),
+            "options": { "raw": { "language": "json" } },
+        }),
+        _ => json!({ "mode": "raw", "raw": "" }),
+    }
+}
+
+/// Postman auth for the first scheme of a requirement that it supports,
+/// credentials are collection variables named after the scheme.
+fn auth(
+    requirement: &specs::SecurityRequirementSpec,
+    components: Option<&ComponentsSpec>,
+    variables: &mut Variables,
+) -> Option<Value> {
+    requirement.keys().find_map(|name| {
+        let scheme = components?.security_schemes.get(name)?.resolve(components)?;
+        let prefix = case::camel_case(name);
+        let mut credential = |suffix: &str| {
+            let key = format!("{}{}", prefix, suffix);
+            variables.insert(&key, "");
+            format!("{{{{{}}}}}", key)
+        };
+
+        match scheme {
+            SecuritySchemeSpec::Http { ref scheme, .. } if scheme.eq_ignore_ascii_case("bearer") => Some(json!({
+                "type": "bearer",
+                "bearer": [{ "key": "token", "value": credential("Token"), "type": "string" }],
+            })),
+            SecuritySchemeSpec::Http { ref scheme, .. } if scheme.eq_ignore_ascii_case("basic") => Some(json!({
+                "type": "basic",
+                "basic": [
+                    { "key": "username", "value": credential("Username"), "type": "string" },
+                    { "key": "password", "value": credential("Password"), "type": "string" },
+                ],
+            })),
+            SecuritySchemeSpec::ApiKey { name: ref key, ref location, .. } if location != "cookie" => Some(json!({
+                "type": "apikey",
+                "apikey": [
+                    { "key": "key", "value": key, "type": "string" },
+                    { "key": "value", "value": credential("Key"), "type": "string" },
+                    { "key": "in", "value": location, "type": "string" },
+                ],
+            })),
+            SecuritySchemeSpec::OAuth2 { .. } | SecuritySchemeSpec::OpenIdConnect { .. } => Some(json!({
+                "type": "oauth2",
+                "oauth2": [{ "key": "accessToken", "value": credential("AccessToken"), "type": "string" }],
+            })),
+            _ => {
+                println!("\tcan't export the security scheme {}", name);
+                None
+            },
+        }
+    })
+}
+
+/// The url of a server with its variables as postman variables.
+fn base_url(server: &ServerSpec) -> String {
+    let mut url = server.url.trim_end_matches('/').to_owned();
+    for name in server.variables.keys() {
+        url = url.replace(&format!("{{{}}}", name), &format!("{{{{{}}}}}", name));
+    }
+    url
+}
+
+fn param_example(schema: &RefOr<SchemaSpec>, components: Option<&ComponentsSpec>) -> String {
+    match example_value(schema, components, Direction::Request) {
+        Value::Array(it) => it.iter().map(text).collect::<Vec<_>>().join(","),
+        it => text(&it),
+    }
+}
+
+/// A json value as it's written in a url or a form.
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(it) => it.clone(),
+        Value::Null => String::new(),
+        it => it.to_string(),
+    }
+}
+
+fn environment_value(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": value, "type": "default", "enabled": true })
+}
+
+fn environment_slug(name: &str, index: usize) -> String {
+    match case::kebab_case(name).as_str() {
+        "" => format!("server-{}", index + 1),
+        it => String::from(it),
+    }
+}
+
+/// The variables of the collection, the first value a name gets is kept.
+#[derive(Default)]
+struct Variables(Vec<(String, String)>);
+
+impl Variables {
+    fn insert(&mut self, key: &str, value: &str) {
+        if !self.0.iter().any(|(it, _)| it == key) {
+            self.0.push((String::from(key), String::from(value)));
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::from(self.0.into_iter()
+            .map(|(key, value)| json!({ "key": key, "value": value, "type": "string" }))
+            .collect::<Vec<_>>())
+    }
+}
+
+#[derive(Serialize)]
+struct PostmanFile {
+    file_name: String,
+    /// The collection or environment as pretty printed json
+    document: String,
+}
+
+impl TemplateContext for PostmanFile {
+    fn template(&self) -> &'static str { "document.tera" }
+    fn filename(&self) -> String { self.file_name.clone() }
+}
@@ -0,0 +1,833 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use serde_derive::Serialize;
+use voca_rs::case;
+use crate::{
+    specs::{
+        self, OpenApiSpec, SchemaSpec, RefOr, ComponentsSpec, PathSpec, OperationSpec,
+    },
+    gen::{
+        Generator, Options, TemplateContext,
+        typescript::{success_response, unsupported_body, ResponseType},
+        models::{
+            self, Language, SCHEMA_PREFIX, schema_type, discriminator_value, type_name,
+            snake_ident, shouty_snake_ident, pascal_ident,
+        },
+    },
+    util,
+};
+
+/// Generates a package with pydantic models for all schemas and httpx based
+/// clients, a sync and an async one, with an api per tag.
+pub struct PythonGenerator;
+impl Generator for PythonGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates("python").unwrap();
+        let package = case::snake_case(&format!("{} client", spec.info.title));
+
+        let mut builder = ModelBuilder::new(spec.components.as_ref(), Python::new(spec.components.as_ref()));
+        builder.add_schemas();
+
+        let apis = generate_apis(spec, &mut builder);
+        let schemes = spec.components.as_ref()
+            .map_or_else(Vec::new, |it| models::security_schemes(it, field_name));
+
+        let servers = spec.servers.iter()
+            .map(|server| {
+                server.variables.iter().fold(server.url.clone(), |url, (name, it)| {
+                    url.replace(&format!("{{{}}}", name), &it.default)
+                })
+            })
+            .collect();
+
+        let modules: Vec<ApiModule> = apis.iter()
+            .map(|it| ApiModule { module: it.module.clone(), class_name: it.class_name.clone() })
+            .collect();
+        let apis_dir = format!("{}/apis", package);
+
+        util::write_templates(&templates, &vec![PyprojectFile {
+            name: case::kebab_case(&package),
+            package: package.clone(),
+            description: spec.info.title.clone(),
+        }], None).unwrap();
+        util::write_templates(&templates, &vec![PackageFile {
+            title: spec.info.title.clone(),
+        }], Some(&package)).unwrap();
+        util::write_templates(&templates, &vec![ModelsFile {
+            models: sort_models(builder.models),
+        }], Some(&package)).unwrap();
+        util::write_templates(&templates, &vec![CoreFile { schemes, servers }], Some(&package)).unwrap();
+        util::write_templates(&templates, &vec![ClientFile {
+            apis: modules.clone(),
+            has_servers: !spec.servers.is_empty(),
+        }], Some(&package)).unwrap();
+        util::write_templates(&templates, &vec![ApisFile { apis: modules }], Some(&apis_dir)).unwrap();
+        util::write_templates(&templates, &apis, Some(&apis_dir)).unwrap();
+    }
+}
+
+/// The python side of the models, which are pydantic classes. Unions are
+/// aliases, discriminated ones pick the variant by a literal field.
+struct Python {
+    /// Discriminator properties and values by the schemas of the union
+    /// variants
+    tags: HashMap<String, (String, String)>,
+}
+
+type ModelBuilder<'a> = models::ModelBuilder<'a, Python>;
+
+impl Python {
+    fn new(components: Option<&ComponentsSpec>) -> Python {
+        let mut tags = HashMap::new();
+        for spec in components.into_iter().flat_map(|it| it.schemas.values()) {
+            let (discriminator, variants) = match spec {
+                RefOr::Object(SchemaSpec { discriminator: Some(ref d), ref one_of, ref any_of, .. }) => {
+                    (d, one_of.iter().chain(any_of.iter()))
+                },
+                _ => continue,
+            };
+
+            for variant in variants {
+                if let RefOr::Ref { ref ref_path } = variant {
+                    if let Some(name) = ref_path.strip_prefix(SCHEMA_PREFIX) {
+                        tags.entry(type_name(name)).or_insert((
+                            discriminator.property_name.clone(),
+                            discriminator_value(discriminator, ref_path),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Python { tags }
+    }
+}
+
+impl Language for Python {
+    type Model = Model;
+
+    const ANY: &'static str = "Any";
+
+    const UNION_ALIASES: bool = true;
+
+    fn primitive_type(spec: &SchemaSpec) -> String {
+        let py_type = match (schema_type(spec), spec.format.as_deref()) {
+            ("string", Some("date-time")) => "dt.datetime",
+            ("string", Some("date")) => "dt.date",
+            ("string", Some("binary")) => "bytes",
+            ("string", _) => "str",
+            ("integer", _) => "int",
+            ("number", _) => "float",
+            ("boolean", _) => "bool",
+            _ => Self::ANY,
+        };
+
+        String::from(py_type)
+    }
+
+    fn array_type(items: &str) -> String {
+        format!("List[{}]", items)
+    }
+
+    fn map_type(values: Option<String>) -> String {
+        format!("Dict[str, {}]", values.as_deref().unwrap_or(Self::ANY))
+    }
+
+    fn nullable_type(value_type: String) -> String {
+        optional(value_type)
+    }
+
+    fn doc(text: &str) -> Option<String> {
+        docstring(text)
+    }
+
+    fn alias(name: String, description: Option<String>, alias: String) -> Model {
+        Model::Alias { name, description, alias }
+    }
+
+    fn enum_type(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let values: Vec<&serde_json::Value> = spec.schema_enum.iter()
+            .filter(|it| !it.is_null())
+            .collect();
+
+        let base = if !values.is_empty() && values.iter().all(|it| it.is_string()) {
+            "str"
+        } else if !values.is_empty() && values.iter().all(|it| it.is_i64() || it.is_u64()) {
+            "int"
+        } else {
+            return Self::primitive_type(spec);
+        };
+
+        let mut variants: Vec<EnumVariant> = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let text = match value.as_str() {
+                Some(it) => String::from(it),
+                None => value.to_string(),
+            };
+            let member = spec.enum_var_names.get(i)
+                .map_or_else(|| member_name(&text), |it| member_name(it));
+
+            let mut unique = member.clone();
+            let mut suffix = 1;
+            while variants.iter().any(|it| it.name == unique) {
+                suffix += 1;
+                unique = format!("{}_{}", member, suffix);
+            }
+
+            variants.push(EnumVariant {
+                name: unique,
+                value: value.to_string(),
+                description: spec.enum_descriptions.get(i).filter(|it| !it.is_empty()).cloned(),
+            });
+        }
+
+        builder.models.push(Model::Enum {
+            name: String::from(name),
+            description: docstring(&spec.description),
+            base: String::from(base),
+            variants,
+        });
+
+        String::from(name)
+    }
+
+    fn object(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let mut fields = tag_field(builder, name);
+        add_fields(builder, &mut fields, spec, name);
+
+        let extra = builder.additional_type(spec, name).is_some();
+        builder.models.push(Model::Class {
+            name: String::from(name),
+            description: docstring(&spec.description),
+            bases: vec![],
+            fields, extra,
+        });
+
+        String::from(name)
+    }
+
+    /// Referenced parts of an intersection become base classes, inline
+    /// objects contribute their properties directly.
+    fn all_of(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let mut bases = vec![];
+        let mut fields = tag_field(builder, name);
+        for (i, part) in spec.all_of.iter().enumerate() {
+            match part {
+                RefOr::Object(ref it) if !it.properties.is_empty() && it.all_of.is_empty()
+                    && it.one_of.is_empty() && it.any_of.is_empty() => {
+                    add_fields(builder, &mut fields, it, name);
+                },
+                _ => bases.push(builder.type_of(part, &format!("{}Part{}", name, i + 1))),
+            }
+        }
+        add_fields(builder, &mut fields, spec, name);
+
+        let extra = builder.additional_type(spec, name).is_some();
+        builder.models.push(Model::Class {
+            name: String::from(name),
+            description: docstring(&spec.description),
+            bases, fields, extra,
+        });
+
+        String::from(name)
+    }
+
+    /// Unions with a discriminator are resolved by its literal field, others
+    /// are tried in order of their declaration.
+    fn union(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let parts = if spec.one_of.is_empty() { &spec.any_of } else { &spec.one_of };
+
+        let variants: Vec<String> = parts.iter()
+            .enumerate()
+            .map(|(i, part)| builder.type_of(part, &format!("{}Variant{}", name, i + 1)))
+            .collect();
+
+        // pydantic needs the literal field on every variant
+        let tags = &builder.language.tags;
+        let tagged = spec.discriminator.as_ref().filter(|discriminator| {
+            variants.len() > 1 && variants.iter().all(|it| {
+                tags.get(it).map(|(property, _)| property) == Some(&discriminator.property_name)
+            })
+        });
+
+        let alias = match (variants.len(), tagged) {
+            (1, _) => variants[0].clone(),
+            (_, Some(discriminator)) => format!(
+                "Annotated[Union[{}], Field(discriminator={})]",
+                variants.join(", "),
+                serde_json::Value::from(field_name(&discriminator.property_name)),
+            ),
+            _ => format!("Union[{}]", variants.join(", ")),
+        };
+
+        builder.models.push(Model::Alias {
+            name: String::from(name),
+            description: docstring(&spec.description),
+            alias,
+        });
+
+        String::from(name)
+    }
+}
+
+/// The literal field of the discriminator, if the model is a variant of
+/// a discriminated union.
+fn tag_field(builder: &ModelBuilder, name: &str) -> Vec<Field> {
+    match builder.language.tags.get(name) {
+        Some((property, value)) => {
+            let ident = field_name(property);
+            let value = serde_json::Value::from(value.as_str()).to_string();
+            vec![Field {
+                value: field_value(Some(&value), alias(&ident, property), None),
+                field_type: format!("Literal[{}]", value),
+                name: ident,
+            }]
+        },
+        None => vec![],
+    }
+}
+
+fn add_fields(builder: &mut ModelBuilder, fields: &mut Vec<Field>, spec: &SchemaSpec, name: &str) {
+    let tag = builder.language.tags.get(name).map(|(property, _)| property.clone());
+
+    for (property, property_spec) in spec.properties.iter() {
+        if tag.as_ref() == Some(property) {
+            continue;
+        }
+
+        let property_type = builder.type_of(property_spec, &format!("{}{}", name, type_name(property)));
+        let required = spec.required.contains(property);
+        let ident = field_name(property);
+        let property_description = builder.description(property_spec);
+
+        fields.push(Field {
+            value: field_value(
+                if required { None } else { Some("None") },
+                alias(&ident, property),
+                property_description.as_deref(),
+            ),
+            field_type: if required { property_type } else { optional(property_type) },
+            name: ident,
+        });
+    }
+}
+
+/// Orders the models so that base classes and the types of aliases are
+/// declared before they are used. Field annotations are evaluated lazily
+/// and can refer to models further down.
+fn sort_models(models: Vec<Model>) -> Vec<Model> {
+    let names: HashSet<String> = models.iter().map(|it| it.name().to_owned()).collect();
+    let mut by_name: HashMap<String, Model> = HashMap::new();
+    let mut order = vec![];
+    for model in models {
+        order.push(model.name().to_owned());
+        by_name.insert(model.name().to_owned(), model);
+    }
+
+    fn visit(
+        name: &str,
+        by_name: &mut HashMap<String, Model>,
+        names: &HashSet<String>,
+        sorted: &mut Vec<Model>,
+    ) {
+        let model = match by_name.remove(name) {
+            Some(it) => it,
+            None => return,
+        };
+
+        let dependencies: Vec<String> = match model {
+            Model::Class { ref bases, .. } => bases.clone(),
+            Model::Alias { ref alias, .. } => alias
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .filter(|it| names.contains(*it) && *it != name)
+                .map(String::from)
+                .collect(),
+            Model::Enum { .. } => vec![],
+        };
+
+        for dependency in dependencies.iter() {
+            visit(dependency, by_name, names, sorted);
+        }
+        sorted.push(model);
+    }
+
+    let mut sorted = vec![];
+    for name in order.iter() {
+        visit(name, &mut by_name, &names, &mut sorted);
+    }
+    sorted
+}
+
+fn generate_apis(spec: &OpenApiSpec, builder: &mut ModelBuilder) -> Vec<ApiFile> {
+    let mut apis: BTreeMap<String, Vec<Operation>> = BTreeMap::new();
+    for tag in spec.tags.iter() {
+        apis.insert(tag.name.clone(), vec![]);
+    }
+
+    for (path, path_spec) in spec.paths.iter() {
+        for (method, op) in path_spec.operations() {
+            let tag = match op.tags.first() {
+                Some(it) => it,
+                None => {
+                    println!("\tskipping untagged operation: {} ({})", op.operation_id, method);
+                    continue;
+                },
+            };
+
+            if let Some(media_type) = unsupported_body(op, builder.components) {
+                println!(
+                    "\tskipping {} ({}), {} request bodies are not supported",
+                    op.operation_id, method, media_type,
+                );
+                continue;
+            }
+
+            let operation = generate_operation(path, path_spec, method, op, &spec.security, builder);
+            apis.entry(tag.clone()).or_default().push(operation);
+        }
+    }
+
+    apis.into_iter()
+        .filter(|(_, operations)| !operations.is_empty())
+        .map(|(tag, operations)| ApiFile {
+            module: field_name(&tag),
+            class_name: format!("{}Api", type_name(&tag)),
+            description: spec.tags.iter()
+                .find(|it| it.name == tag)
+                .and_then(|it| docstring(&it.description)),
+            operations,
+        })
+        .collect()
+}
+
+fn generate_operation(
+    path: &str,
+    path_spec: &PathSpec,
+    method: &str,
+    spec: &OperationSpec,
+    security: &[specs::SecurityRequirementSpec],
+    builder: &mut ModelBuilder,
+) -> Operation {
+    let name = pascal_ident(&spec.operation_id);
+
+    let mut path_params = vec![];
+    let mut query_params = vec![];
+    let mut header_params = vec![];
+    for param in path_spec.params(spec) {
+        let param_type = builder.type_of(&param.schema, &format!("{}{}", name, type_name(&param.name)));
+        let field = Param {
+            ident: field_name(&param.name),
+            name: param.name.clone(),
+            // absent parameters are optional already
+            param_type: strip_optional(&param_type).to_owned(),
+            required: param.required || param.location == "path",
+            description: param.description.clone(),
+        };
+
+        match param.location.as_str() {
+            "path" => path_params.push(field),
+            "query" => query_params.push(field),
+            "header" => header_params.push(field),
+            other => println!("\tskipping {} parameter {} of {}", other, param.name, spec.operation_id),
+        }
+    }
+
+    for placeholder in specs::path_placeholders(path) {
+        if path_params.iter().any(|it: &Param| it.name == placeholder) {
+            continue;
+        }
+
+        println!("\t{} has no parameter for {{{}}}, it takes a string", spec.operation_id, placeholder);
+        path_params.push(Param {
+            ident: field_name(placeholder),
+            name: String::from(placeholder),
+            param_type: String::from("str"),
+            required: true,
+            description: None,
+        });
+    }
+    path_params.retain(|it| {
+        let is_placeholder = path.contains(&format!("{{{}}}", it.name));
+        if !is_placeholder {
+            println!(
+                "\tskipping path parameter {} of {}, the path has no placeholder for it",
+                it.name, spec.operation_id,
+            );
+        }
+        is_placeholder
+    });
+
+    // path params are passed in the order of the path
+    path_params.sort_by_key(|it| path.find(&format!("{{{}}}", it.name)));
+
+    let body = spec.request_body.as_ref()
+        .and_then(|it| it.resolve(builder.components))
+        .and_then(|body_spec| {
+            let schema = specs::json_schema(&body_spec.content)?;
+            let body_type = builder.type_of(&schema, &format!("{}Request", name));
+            Some(Body { body_type, required: body_spec.required })
+        });
+
+    // path params and a required body are positional, everything else has
+    // to be passed by keyword
+    let mut positional: Vec<Argument> = path_params.iter()
+        .map(|it| Argument::new(&it.ident, it.param_type.clone(), None))
+        .collect();
+    let mut keyword = vec![];
+    let other_params: Vec<&Param> = query_params.iter().chain(header_params.iter()).collect();
+    for param in other_params.iter().filter(|it| it.required) {
+        keyword.push(Argument::new(&param.ident, param.param_type.clone(), None));
+    }
+    match body {
+        Some(ref it) if it.required => positional.push(Argument::new("body", it.body_type.clone(), None)),
+        _ => {},
+    }
+    for param in other_params.iter().filter(|it| !it.required) {
+        keyword.push(Argument::new(&param.ident, optional(param.param_type.clone()), Some("None")));
+    }
+    match body {
+        Some(ref it) if !it.required => keyword.push(Argument::new("body", optional(it.body_type.clone()), Some("None"))),
+        _ => {},
+    }
+
+    let param_docs: Vec<String> = path_params.iter()
+        .chain(other_params.iter().cloned())
+        .filter_map(|param| {
+            let description = param.description.as_ref()?;
+            Some(format!(":param {}: {}", param.ident, description.replace('\n', " ")))
+        })
+        .collect();
+    let doc = spec.summary.iter().cloned()
+        .chain(Some(param_docs.join("\n")).filter(|it| !it.is_empty()))
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    let path_format = path_params.iter().fold(String::from(path), |path, param| {
+        path.replace(&format!("{{{}}}", param.name), &format!("{{core.path_param({})}}", param.ident))
+    });
+
+    let (accept, response_schema) = success_response(&spec.responses, builder.components)
+        .unwrap_or_default();
+    let (response_kind, return_type) = match accept.first().map(|it| ResponseType::from_media_type(it)) {
+        None => ("empty", String::from("None")),
+        Some(ResponseType::Json) => match response_schema {
+            Some(schema) => ("json", builder.type_of(&schema, &format!("{}Response", name))),
+            None => ("json", String::from("Any")),
+        },
+        Some(ResponseType::Text) => ("text", String::from("str")),
+        Some(ResponseType::Blob) | Some(ResponseType::ArrayBuffer) => ("bytes", String::from("bytes")),
+        // the events are read from the response as they arrive
+        Some(ResponseType::EventStream) => ("stream", String::from("httpx.Response")),
+    };
+
+    let has_success = spec.responses.keys().any(|it| it.starts_with('2'));
+    let errors = spec.responses.iter()
+        .filter(|(status, _)| {
+            status.starts_with('4') || status.starts_with('5')
+                || (has_success && status.as_str() == "default")
+        })
+        .map(|(status, response)| {
+            let variant = if status == "default" {
+                String::from("Default")
+            } else {
+                format!("Status{}", status.to_uppercase())
+            };
+
+            ErrorResponse {
+                status: if status == "default" { status.clone() } else { status.to_uppercase() },
+                body_type: builder.response_type(response, &format!("{}{}Error", name, variant)),
+            }
+        })
+        .collect();
+
+    Operation {
+        name: field_name(&spec.operation_id),
+        doc: docstring(&doc),
+        deprecated: spec.deprecated,
+        method: method.to_uppercase(),
+        path_format,
+        positional, keyword,
+        query_params, header_params,
+        has_body: body.is_some(),
+        accept: accept.first().cloned(),
+        response_kind: String::from(response_kind),
+        return_type, errors,
+        security: spec.security.as_ref().map_or(security, |it| it.as_slice())
+            .iter()
+            .filter(|it| !it.is_empty())
+            .map(|it| it.keys().cloned().collect())
+            .collect(),
+    }
+}
+
+fn optional(py_type: String) -> String {
+    if py_type.starts_with("Optional[") || py_type == "Any" {
+        py_type
+    } else {
+        format!("Optional[{}]", py_type)
+    }
+}
+
+fn strip_optional(py_type: &str) -> &str {
+    py_type.strip_prefix("Optional[")
+        .and_then(|it| it.strip_suffix(']'))
+        .unwrap_or(py_type)
+}
+
+/// The arguments of `Field` for a field, `None` if it needs none.
+fn field_value(default: Option<&str>, alias: Option<&str>, description: Option<&str>) -> Option<String> {
+    let mut args = vec![];
+    if let Some(default) = default {
+        args.push(format!("default={}", default));
+    }
+    if let Some(alias) = alias {
+        args.push(format!("alias={}", serde_json::Value::from(alias)));
+    }
+    if let Some(description) = description {
+        args.push(format!("description={}", serde_json::Value::from(description)));
+    }
+
+    match args.as_slice() {
+        [] => None,
+        // a plain default reads better than a Field
+        [_] if alias.is_none() && description.is_none() => default.map(String::from),
+        _ => Some(format!("Field({})", args.join(", "))),
+    }
+}
+
+fn alias<'a>(ident: &str, property: &'a str) -> Option<&'a str> {
+    Some(property).filter(|it| *it != ident)
+}
+
+/// Escapes a text for a docstring, `None` if it is empty.
+fn docstring(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    Some(text.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\""))
+}
+
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+    "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+    "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+    "try", "while", "with", "yield",
+    // attributes of pydantic models, fields must not shadow them
+    "copy", "dict", "json", "schema", "schema_json", "construct", "validate", "model_config",
+    "model_fields", "model_computed_fields",
+];
+
+/// Converts a property or parameter name into a snake case identifier,
+/// keywords get an underscore appended.
+fn field_name(name: &str) -> String {
+    let ident = snake_ident(name);
+    match ident.as_str() {
+        "" => String::from("value"),
+        // leading underscores would make the field private
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("field_{}", it),
+        it if KEYWORDS.contains(&it) => format!("{}_", it),
+        _ => ident,
+    }
+}
+
+/// Converts an enum value into a member name, e.g. `per-stay` to `PER_STAY`.
+fn member_name(value: &str) -> String {
+    let ident = shouty_snake_ident(value);
+    match ident.as_str() {
+        "" if value.starts_with('-') => String::from("MINUS"),
+        "" => String::from("EMPTY"),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("VALUE_{}", it),
+        _ => ident,
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type")]
+enum Model {
+    Class {
+        name: String,
+        description: Option<String>,
+        /// Referenced parts of an intersection, `BaseModel` if there are none
+        bases: Vec<String>,
+        fields: Vec<Field>,
+        /// Whether additional properties are kept
+        extra: bool,
+    },
+    Enum {
+        name: String,
+        description: Option<String>,
+        /// `str` or `int`
+        base: String,
+        variants: Vec<EnumVariant>,
+    },
+    Alias {
+        name: String,
+        description: Option<String>,
+        alias: String,
+    },
+}
+
+impl Model {
+    fn name(&self) -> &str {
+        match self {
+            Model::Class { ref name, .. } | Model::Enum { ref name, .. } | Model::Alias { ref name, .. } => name,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Field {
+    name: String,
+    field_type: String,
+    /// The default or the `Field` with the alias and description
+    value: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct EnumVariant {
+    name: String,
+    /// The value as python literal
+    value: String,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Param {
+    name: String,
+    ident: String,
+    param_type: String,
+    required: bool,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Body {
+    body_type: String,
+    required: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Argument {
+    ident: String,
+    annotation: String,
+    default: Option<String>,
+}
+
+impl Argument {
+    fn new(ident: &str, annotation: String, default: Option<&str>) -> Argument {
+        Argument { ident: String::from(ident), annotation, default: default.map(String::from) }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ErrorResponse {
+    /// The status code, a range like `4XX` or `default`
+    status: String,
+    body_type: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Operation {
+    name: String,
+    doc: Option<String>,
+    deprecated: bool,
+    method: String,
+    /// The path as f-string with the encoded path params
+    path_format: String,
+    positional: Vec<Argument>,
+    keyword: Vec<Argument>,
+    query_params: Vec<Param>,
+    header_params: Vec<Param>,
+    has_body: bool,
+    accept: Option<String>,
+    /// How the response body is read: json, text, bytes, stream or empty
+    response_kind: String,
+    return_type: String,
+    errors: Vec<ErrorResponse>,
+    security: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ApiModule {
+    module: String,
+    class_name: String,
+}
+
+#[derive(Serialize)]
+struct PyprojectFile {
+    name: String,
+    package: String,
+    description: String,
+}
+
+impl TemplateContext for PyprojectFile {
+    fn template(&self) -> &'static str { "pyproject.tera" }
+    fn filename(&self) -> String { String::from("pyproject.toml") }
+}
+
+#[derive(Serialize)]
+struct PackageFile {
+    title: String,
+}
+
+impl TemplateContext for PackageFile {
+    fn template(&self) -> &'static str { "package.tera" }
+    fn filename(&self) -> String { String::from("__init__.py") }
+}
+
+#[derive(Serialize)]
+struct ModelsFile {
+    models: Vec<Model>,
+}
+
+impl TemplateContext for ModelsFile {
+    fn template(&self) -> &'static str { "models.tera" }
+    fn filename(&self) -> String { String::from("models.py") }
+}
+
+#[derive(Serialize)]
+struct CoreFile {
+    schemes: Vec<models::SecurityScheme>,
+    /// Server urls with the defaults of their variables
+    servers: Vec<String>,
+}
+
+impl TemplateContext for CoreFile {
+    fn template(&self) -> &'static str { "core.tera" }
+    fn filename(&self) -> String { String::from("core.py") }
+}
+
+#[derive(Serialize)]
+struct ClientFile {
+    apis: Vec<ApiModule>,
+    has_servers: bool,
+}
+
+impl TemplateContext for ClientFile {
+    fn template(&self) -> &'static str { "client.tera" }
+    fn filename(&self) -> String { String::from("client.py") }
+}
+
+#[derive(Serialize)]
+struct ApisFile {
+    apis: Vec<ApiModule>,
+}
+
+impl TemplateContext for ApisFile {
+    fn template(&self) -> &'static str { "apis.tera" }
+    fn filename(&self) -> String { String::from("__init__.py") }
+}
+
+#[derive(Serialize)]
+struct ApiFile {
+    module: String,
+    class_name: String,
+    description: Option<String>,
+    operations: Vec<Operation>,
+}
+
+impl TemplateContext for ApiFile {
+    fn template(&self) -> &'static str { "api.tera" }
+    fn filename(&self) -> String { format!("{}.py", self.module) }
+}
@@ -0,0 +1,962 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use serde_derive::Serialize;
+use voca_rs::case;
+use crate::{
+    specs::{
+        self, OpenApiSpec, SchemaSpec, RefOr, ComponentsSpec, PathSpec, OperationSpec,
+    },
+    gen::{
+        Generator, Options, TemplateContext,
+        typescript::{success_response, unsupported_body, ResponseType},
+        models::{
+            self, Language, SCHEMA_PREFIX, schema_type, non_empty, discriminator_value,
+            type_name, snake_ident, pascal_ident,
+        },
+    },
+    util,
+};
+
+/// Generates a crate with serde models for all schemas and an async client
+/// on top of reqwest with a method per operation.
+pub struct RustClientGenerator;
+impl Generator for RustClientGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates_with("rust-client", &["rust"]).unwrap();
+        let crate_name = format!("{}-client", case::kebab_case(&spec.info.title));
+
+        let mut builder = ModelBuilder::new(spec.components.as_ref(), Rust::new(spec.components.as_ref()));
+        builder.add_schemas();
+
+        let apis = generate_apis(spec, &mut builder);
+        let schemes = spec.components.as_ref()
+            .map_or_else(Vec::new, |it| models::security_schemes(it, field_name));
+
+        let servers = spec.servers.iter()
+            .map(|server| {
+                // variables are substituted with their defaults, other
+                // urls can be passed to the client directly
+                server.variables.iter().fold(server.url.clone(), |url, (name, it)| {
+                    url.replace(&format!("{{{}}}", name), &it.default)
+                })
+            })
+            .collect();
+
+        util::write_templates(&templates, &vec![CargoFile {
+            name: crate_name,
+            description: spec.info.title.clone(),
+        }], None).unwrap();
+        util::write_templates(&templates, &vec![LibFile {}], Some("src")).unwrap();
+        util::write_templates(&templates, &vec![ModelsFile { models: builder.models }], Some("src")).unwrap();
+        util::write_templates(&templates, &vec![ClientFile { schemes, servers }], Some("src")).unwrap();
+        util::write_templates(&templates, &vec![ApisFile {
+            modules: apis.iter().map(|it| it.module.clone()).collect(),
+        }], Some("src/apis")).unwrap();
+        util::write_templates(&templates, &apis, Some("src/apis")).unwrap();
+    }
+}
+
+/// Generates a crate with an axum router for the operations, which forwards
+/// the requests to an implementation of a trait with a method per operation.
+/// The models are the same as the ones of the client.
+pub struct RustServerGenerator;
+impl Generator for RustServerGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates_with("rust-server", &["rust"]).unwrap();
+        let crate_name = format!("{}-server", case::kebab_case(&spec.info.title));
+
+        let mut builder = ModelBuilder::new(spec.components.as_ref(), Rust::new(spec.components.as_ref()));
+        builder.add_schemas();
+
+        let (routes, handlers) = generate_routes(spec, &mut builder);
+
+        util::write_templates(&templates, &vec![CargoFile {
+            name: crate_name,
+            description: spec.info.title.clone(),
+        }], None).unwrap();
+        util::write_templates(&templates, &vec![ServerLibFile {}], Some("src")).unwrap();
+        util::write_templates(&templates, &vec![ModelsFile { models: builder.models }], Some("src")).unwrap();
+        util::write_templates(&templates, &vec![ServerApiFile { handlers: handlers.clone() }], Some("src")).unwrap();
+        util::write_templates(&templates, &vec![RouterFile { routes, handlers }], Some("src")).unwrap();
+    }
+}
+
+/// The rust side of the models, unions are enums and intersections are
+/// flattened into structs.
+struct Rust {
+    /// Models that implement `Display`, which can be sent as parameters
+    enums: HashSet<String>,
+    /// Discriminator properties by the schemas of the union variants, the
+    /// union reads and writes them instead of the variants
+    tags: HashMap<String, String>,
+}
+
+type ModelBuilder<'a> = models::ModelBuilder<'a, Rust>;
+
+impl Rust {
+    fn new(components: Option<&ComponentsSpec>) -> Rust {
+        let mut tags = HashMap::new();
+        for spec in components.into_iter().flat_map(|it| it.schemas.values()) {
+            let (discriminator, variants) = match spec {
+                RefOr::Object(SchemaSpec { discriminator: Some(ref d), ref one_of, ref any_of, .. }) => {
+                    (d, one_of.iter().chain(any_of.iter()))
+                },
+                _ => continue,
+            };
+
+            for variant in variants {
+                if let RefOr::Ref { ref ref_path } = variant {
+                    if let Some(name) = ref_path.strip_prefix(SCHEMA_PREFIX) {
+                        tags.insert(type_name(name), discriminator.property_name.clone());
+                    }
+                }
+            }
+        }
+
+        Rust { enums: HashSet::new(), tags }
+    }
+
+    /// Whether values of the type can be formatted as parameters directly.
+    fn is_display(&self, rust_type: &str) -> bool {
+        match rust_type {
+            "String" | "bool" | "i32" | "i64" | "f32" | "f64" => true,
+            it => self.enums.contains(it),
+        }
+    }
+}
+
+impl Language for Rust {
+    type Model = Model;
+
+    const ANY: &'static str = "serde_json::Value";
+
+    fn primitive_type(spec: &SchemaSpec) -> String {
+        let rust_type = match (schema_type(spec), spec.format.as_deref()) {
+            ("string", _) => "String",
+            ("integer", Some("int32")) => "i32",
+            ("integer", _) => "i64",
+            ("number", Some("float")) => "f32",
+            ("number", _) => "f64",
+            ("boolean", _) => "bool",
+            _ => Self::ANY,
+        };
+
+        String::from(rust_type)
+    }
+
+    fn array_type(items: &str) -> String {
+        format!("Vec<{}>", items)
+    }
+
+    fn map_type(values: Option<String>) -> String {
+        match values {
+            Some(value) => format!("HashMap<String, {}>", value),
+            None => String::from(Self::ANY),
+        }
+    }
+
+    fn nullable_type(value_type: String) -> String {
+        optional(value_type)
+    }
+
+    fn alias(name: String, description: Option<String>, alias: String) -> Model {
+        Model::Alias { name, description, alias }
+    }
+
+    fn enum_type(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let values: Vec<&serde_json::Value> = spec.schema_enum.iter()
+            .filter(|it| !it.is_null())
+            .collect();
+
+        // serde can only rename unit variants to strings
+        if values.is_empty() || !values.iter().all(|it| it.is_string()) {
+            return Self::primitive_type(spec);
+        }
+
+        let mut variants: Vec<EnumVariant> = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let value = value.as_str().unwrap_or_default();
+            let base = spec.enum_var_names.get(i)
+                .map_or_else(|| variant_name(value), |it| variant_name(it));
+
+            let mut variant = base.clone();
+            let mut suffix = 1;
+            while variants.iter().any(|it| it.name == variant) {
+                suffix += 1;
+                variant = format!("{}{}", base, suffix);
+            }
+
+            variants.push(EnumVariant {
+                name: variant,
+                value: String::from(value),
+                description: spec.enum_descriptions.get(i).filter(|it| !it.is_empty()).cloned(),
+            });
+        }
+
+        builder.language.enums.insert(String::from(name));
+        builder.models.push(Model::Enum {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            variants,
+        });
+
+        String::from(name)
+    }
+
+    fn object(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let mut fields = vec![];
+        add_fields(builder, &mut fields, spec, name);
+
+        let additional = builder.additional_type(spec, name);
+        builder.models.push(Model::Struct {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            fields, additional,
+        });
+
+        String::from(name)
+    }
+
+    /// All parts of an intersection are flattened into a struct, inline
+    /// objects contribute their properties directly.
+    fn all_of(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let mut fields = vec![];
+        for (i, part) in spec.all_of.iter().enumerate() {
+            match part {
+                RefOr::Object(ref it) if !it.properties.is_empty() && it.all_of.is_empty()
+                    && it.one_of.is_empty() && it.any_of.is_empty() => {
+                    add_fields(builder, &mut fields, it, name);
+                },
+                _ => {
+                    let part_type = field_type(builder, part, &format!("{}Part{}", name, i + 1));
+                    let part_name = match part {
+                        RefOr::Ref { ref ref_path } => field_name(ref_path.rsplit('/').next().unwrap_or_default()),
+                        _ => format!("part{}", i + 1),
+                    };
+
+                    fields.push(Field {
+                        name: part_name,
+                        rename: None,
+                        field_type: part_type,
+                        optional: false,
+                        flatten: true,
+                        description: None,
+                    });
+                },
+            }
+        }
+        add_fields(builder, &mut fields, spec, name);
+
+        let additional = builder.additional_type(spec, name);
+        builder.models.push(Model::Struct {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            fields, additional,
+        });
+
+        String::from(name)
+    }
+
+    /// Unions with a discriminator are tagged by it, others are tried in
+    /// order of their declaration.
+    fn union(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let parts = if spec.one_of.is_empty() { &spec.any_of } else { &spec.one_of };
+
+        let mut variants: Vec<UnionVariant> = vec![];
+        for (i, part) in parts.iter().enumerate() {
+            let (variant, tag) = match part {
+                RefOr::Ref { ref ref_path } => {
+                    let schema = ref_path.rsplit('/').next().unwrap_or_default();
+                    let tag = spec.discriminator.as_ref().map(|it| discriminator_value(it, ref_path));
+                    (type_name(schema), tag)
+                },
+                RefOr::Object(_) => (format!("Variant{}", i + 1), None),
+            };
+
+            let variant_type = field_type(builder, part, &format!("{}Variant{}", name, i + 1));
+            variants.push(UnionVariant {
+                rename: tag.filter(|it| *it != variant),
+                name: variant,
+                variant_type,
+            });
+        }
+
+        builder.models.push(Model::Union {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            tag: spec.discriminator.as_ref().map(|it| it.property_name.clone()),
+            variants,
+        });
+
+        String::from(name)
+    }
+}
+
+/// Fields referencing a model of a cycle have to be boxed, collections
+/// are boxes already.
+fn field_type(builder: &mut ModelBuilder, spec: &RefOr<SchemaSpec>, name: &str) -> String {
+    let field_type = builder.type_of(spec, name);
+    if builder.is_cyclic_ref(spec) {
+        match field_type.strip_prefix("Option<").and_then(|it| it.strip_suffix('>')) {
+            Some(value_type) => format!("Option<Box<{}>>", value_type),
+            None => format!("Box<{}>", field_type),
+        }
+    } else {
+        field_type
+    }
+}
+
+fn add_fields(builder: &mut ModelBuilder, fields: &mut Vec<Field>, spec: &SchemaSpec, name: &str) {
+    let tag = builder.language.tags.get(name).cloned();
+
+    for (property, property_spec) in spec.properties.iter() {
+        if tag.as_ref() == Some(property) {
+            continue;
+        }
+
+        let property_type = field_type(builder, property_spec, &format!("{}{}", name, type_name(property)));
+        let required = spec.required.contains(property);
+        let ident = field_name(property);
+
+        fields.push(Field {
+            rename: if ident.trim_start_matches("r#") != property { Some(property.clone()) } else { None },
+            name: ident,
+            optional: !required || property_type.starts_with("Option<"),
+            field_type: if required { property_type } else { optional(property_type) },
+            flatten: false,
+            description: builder.description(property_spec),
+        });
+    }
+}
+
+fn generate_apis(spec: &OpenApiSpec, builder: &mut ModelBuilder) -> Vec<ApiFile> {
+    let mut apis: BTreeMap<String, Vec<Operation>> = BTreeMap::new();
+    for tag in spec.tags.iter() {
+        apis.insert(tag.name.clone(), vec![]);
+    }
+
+    for (path, path_spec) in spec.paths.iter() {
+        for (method, op) in path_spec.operations() {
+            let tag = match op.tags.first() {
+                Some(it) => it,
+                None => {
+                    println!("\tskipping untagged operation: {} ({})", op.operation_id, method);
+                    continue;
+                },
+            };
+
+            if let Some(media_type) = unsupported_body(op, builder.components) {
+                println!(
+                    "\tskipping {} ({}), {} request bodies are not supported",
+                    op.operation_id, method, media_type,
+                );
+                continue;
+            }
+
+            let operation = generate_operation(path, path_spec, method, op, &spec.security, builder);
+            apis.entry(tag.clone()).or_default().push(operation);
+        }
+    }
+
+    apis.into_iter()
+        .filter(|(_, operations)| !operations.is_empty())
+        .map(|(tag, operations)| ApiFile {
+            module: field_name(&tag).trim_start_matches("r#").to_owned(),
+            operations,
+        })
+        .collect()
+}
+
+fn generate_operation(
+    path: &str,
+    path_spec: &PathSpec,
+    method: &str,
+    spec: &OperationSpec,
+    security: &[specs::SecurityRequirementSpec],
+    builder: &mut ModelBuilder,
+) -> Operation {
+    let name = pascal_ident(&spec.operation_id);
+    let (path_params, query_params, header_params) = generate_params(path, path_spec, spec, builder);
+    let url_format = path_params.iter().fold(String::from(path), |url, param| {
+        url.replace(&format!("{{{}}}", param.name), "{}")
+    });
+    let body = generate_body(spec, &name, builder);
+
+    let (accept, response_schema) = success_response(&spec.responses, builder.components)
+        .unwrap_or_default();
+    let (response_kind, return_type) = match accept.first().map(|it| ResponseType::from_media_type(it)) {
+        None => ("empty", String::from("()")),
+        Some(ResponseType::Json) => match response_schema {
+            Some(schema) => ("json", builder.type_of(&schema, &format!("{}Response", name))),
+            None => ("json", String::from("serde_json::Value")),
+        },
+        Some(ResponseType::Text) => ("text", String::from("String")),
+        Some(ResponseType::Blob) | Some(ResponseType::ArrayBuffer) => ("bytes", String::from("Vec<u8>")),
+        // the events are read from the response as they arrive
+        Some(ResponseType::EventStream) => ("stream", String::from("reqwest::Response")),
+    };
+
+    let has_success = spec.responses.keys().any(|it| it.starts_with('2'));
+    let mut errors: Vec<ErrorVariant> = spec.responses.iter()
+        .filter(|(status, _)| is_error(status, has_success))
+        .map(|(status, response)| {
+            let variant = status_variant(status);
+
+            let status_match = match status.as_str() {
+                "default" => String::from("_"),
+                it if it.parse::<u16>().is_ok() => String::from(it),
+                it => {
+                    let class = &it[..1];
+                    format!("{}00..={}99", class, class)
+                },
+            };
+
+            ErrorVariant {
+                // ranges and the default carry the actual status
+                with_status: status_match.contains("..=") || status_match == "_",
+                body_type: builder.response_type(response, &format!("{}{}Error", name, variant)),
+                description: response.resolve(builder.components).and_then(|it| it.description.clone()),
+                status: status.clone(),
+                status_match,
+                name: variant,
+            }
+        })
+        .collect();
+
+    // exact statuses have to be matched before ranges and the default
+    errors.sort_by_key(|it| (it.status_match == "_", it.status_match.contains("..=")));
+    let has_default = errors.iter().any(|it| it.status_match == "_");
+
+    Operation {
+        name: field_name(&spec.operation_id).trim_start_matches("r#").to_owned(),
+        error_type: format!("{}Error", name),
+        summary: spec.summary.clone(),
+        deprecated: spec.deprecated,
+        method: method.to_uppercase(),
+        url_format,
+        path_params, query_params, header_params, body,
+        accept: accept.first().cloned(),
+        response_kind: String::from(response_kind),
+        return_type, errors, has_default,
+        security: spec.security.as_ref().map_or(security, |it| it.as_slice())
+            .iter()
+            .filter(|it| !it.is_empty())
+            .map(|it| it.keys().cloned().collect())
+            .collect(),
+    }
+}
+
+/// Collects the path, query and header params of an operation, the path
+/// params in the order they appear in the path. Placeholders of the path
+/// without a param get one that takes a string.
+fn generate_params(
+    path: &str,
+    path_spec: &PathSpec,
+    spec: &OperationSpec,
+    builder: &mut ModelBuilder,
+) -> (Vec<Param>, Vec<Param>, Vec<Param>) {
+    let name = pascal_ident(&spec.operation_id);
+
+    let mut path_params = vec![];
+    let mut query_params = vec![];
+    let mut header_params = vec![];
+    let mut idents: Vec<String> = LOCALS.iter().map(|it| String::from(*it)).collect();
+    for param in path_spec.params(spec) {
+        let param_type = builder.type_of(&param.schema, &format!("{}{}", name, type_name(&param.name)));
+        // absent parameters are optional already
+        let param_type = strip_type(&param_type, "Option").to_owned();
+        let items = param_type.strip_prefix("Vec<").map(|_| strip_type(&param_type, "Vec"));
+
+        let field = Param {
+            ident: param_ident(&param.name, &param.location, &mut idents),
+            name: param.name.clone(),
+            arg_type: match (param_type.as_str(), items) {
+                ("String", _) => String::from("&str"),
+                (_, Some(items)) => format!("&[{}]", items),
+                (it, None) if builder.language.is_display(it) => param_type.clone(),
+                _ => format!("&{}", param_type),
+            },
+            required: param.required || param.location == "path",
+            is_array: items.is_some(),
+            display: builder.language.is_display(items.unwrap_or(&param_type)),
+            description: param.description.clone(),
+            param_type,
+        };
+
+        match param.location.as_str() {
+            "path" => path_params.push(field),
+            "query" => query_params.push(field),
+            "header" => header_params.push(field),
+            other => println!("\tskipping {} parameter {} of {}", other, param.name, spec.operation_id),
+        }
+    }
+
+    for placeholder in specs::path_placeholders(path) {
+        if path_params.iter().any(|it: &Param| it.name == placeholder) {
+            continue;
+        }
+
+        println!("\t{} has no parameter for {{{}}}, it takes a string", spec.operation_id, placeholder);
+        path_params.push(Param {
+            ident: param_ident(placeholder, "path", &mut idents),
+            name: String::from(placeholder),
+            arg_type: String::from("&str"),
+            required: true,
+            is_array: false,
+            display: true,
+            description: None,
+            param_type: String::from("String"),
+        });
+    }
+
+    let mut ordered: Vec<(usize, Param)> = path_params.into_iter()
+        .filter_map(|it| match path.find(&format!("{{{}}}", it.name)) {
+            Some(i) => Some((i, it)),
+            None => {
+                println!(
+                    "\tskipping path parameter {} of {}, the path has no placeholder for it",
+                    it.name, spec.operation_id,
+                );
+                None
+            },
+        })
+        .collect();
+    ordered.sort_by_key(|(i, _)| *i);
+
+    (ordered.into_iter().map(|(_, it)| it).collect(), query_params, header_params)
+}
+
+/// Names the client and router functions use for their own variables.
+const LOCALS: &[&str] = &["api", "path", "query", "headers", "body", "url", "request", "response", "status"];
+
+/// The argument name of a parameter, names that are already taken by another
+/// parameter or a variable get the location appended, e.g. `limit_header`.
+fn param_ident(name: &str, location: &str, idents: &mut Vec<String>) -> String {
+    let ident = field_name(name);
+    let mut unique = ident.clone();
+    let mut suffix = 1;
+    while idents.contains(&unique) {
+        suffix += 1;
+        unique = match suffix {
+            2 => format!("{}_{}", ident.trim_start_matches("r#"), location),
+            _ => format!("{}_{}{}", ident.trim_start_matches("r#"), location, suffix),
+        };
+    }
+
+    idents.push(unique.clone());
+    unique
+}
+
+fn generate_body(spec: &OperationSpec, name: &str, builder: &mut ModelBuilder) -> Option<Body> {
+    spec.request_body.as_ref()
+        .and_then(|it| it.resolve(builder.components))
+        .and_then(|body_spec| {
+            let schema = specs::json_schema(&body_spec.content)?;
+            let body_type = builder.type_of(&schema, &format!("{}Request", name));
+            Some(Body { body_type, required: body_spec.required })
+        })
+}
+
+fn generate_routes(spec: &OpenApiSpec, builder: &mut ModelBuilder) -> (Vec<Route>, Vec<Handler>) {
+    let mut routes = vec![];
+    let mut handlers = vec![];
+    for (path, path_spec) in spec.paths.iter() {
+        let mut route = Route {
+            // axum captures path segments as `:name`
+            path: path.replace('{', ":").replace('}', ""),
+            methods: vec![],
+        };
+
+        for (method, op) in path_spec.operations() {
+            if let Some(media_type) = unsupported_body(op, builder.components) {
+                println!(
+                    "\tskipping {} ({}), {} request bodies are not supported",
+                    op.operation_id, method, media_type,
+                );
+                continue;
+            }
+
+            let handler = generate_handler(path, path_spec, method, op, builder);
+            route.methods.push((String::from(method), handler.name.clone()));
+            handlers.push(handler);
+        }
+
+        if !route.methods.is_empty() {
+            routes.push(route);
+        }
+    }
+
+    (routes, handlers)
+}
+
+fn generate_handler(
+    path: &str,
+    path_spec: &PathSpec,
+    method: &str,
+    spec: &OperationSpec,
+    builder: &mut ModelBuilder,
+) -> Handler {
+    let name = pascal_ident(&spec.operation_id);
+    let (path_params, query_params, header_params) = generate_params(path, path_spec, spec, builder);
+    let body = generate_body(spec, &name, builder);
+
+    let has_success = spec.responses.keys().any(|it| it.starts_with('2'));
+    // the body of the response the client returns keeps the same name
+    let success = spec.responses.keys()
+        .find(|it| it.starts_with('2'))
+        .or_else(|| spec.responses.keys().find(|it| it.as_str() == "default"))
+        .cloned();
+
+    let responses = spec.responses.iter()
+        .map(|(status, response)| {
+            let variant = status_variant(status);
+            let body_name = if Some(status) == success.as_ref() {
+                format!("{}Response", name)
+            } else if is_error(status, has_success) {
+                format!("{}{}Error", name, variant)
+            } else {
+                format!("{}{}Response", name, variant)
+            };
+
+            let resolved = response.resolve(builder.components);
+            let media_type = resolved.and_then(|it| {
+                it.content.keys()
+                    .find(|it| specs::is_json_media_type(&specs::media_type_essence(it)))
+                    .or_else(|| it.content.keys().next())
+                    .map(|it| specs::media_type_essence(it))
+            });
+
+            let (kind, body_type) = match media_type.as_ref().map(|it| ResponseType::from_media_type(it)) {
+                None => ("empty", None),
+                Some(ResponseType::Json) => {
+                    let body_type = builder.response_type(response, &body_name)
+                        .unwrap_or_else(|| String::from("serde_json::Value"));
+                    ("json", Some(body_type))
+                },
+                Some(ResponseType::Text) => ("text", Some(String::from("String"))),
+                Some(ResponseType::Blob) | Some(ResponseType::ArrayBuffer) => {
+                    ("bytes", Some(String::from("Vec<u8>")))
+                },
+                Some(ResponseType::EventStream) => ("stream", Some(String::from("axum::body::Body"))),
+            };
+
+            HandlerResponse {
+                code: status.parse().ok(),
+                description: resolved.and_then(|it| it.description.clone()),
+                kind: String::from(kind),
+                status: status.clone(),
+                name: variant,
+                body_type, media_type,
+            }
+        })
+        .collect();
+
+    Handler {
+        name: field_name(&spec.operation_id).trim_start_matches("r#").to_owned(),
+        reply_type: format!("{}Reply", name),
+        type_name: name,
+        summary: spec.summary.clone(),
+        deprecated: spec.deprecated,
+        path_params, query_params, header_params, body,
+        responses,
+    }
+}
+
+/// Whether a response is an error, the default response is the success
+/// if the operation declares no other one.
+fn is_error(status: &str, has_success: bool) -> bool {
+    status.starts_with('4') || status.starts_with('5') || (has_success && status == "default")
+}
+
+/// The name of the variant of a response, e.g. `Status404` or `Status4XX`.
+fn status_variant(status: &str) -> String {
+    if status == "default" {
+        String::from("Default")
+    } else {
+        format!("Status{}", status.to_uppercase())
+    }
+}
+
+fn optional(rust_type: String) -> String {
+    if rust_type.starts_with("Option<") {
+        rust_type
+    } else {
+        format!("Option<{}>", rust_type)
+    }
+}
+
+/// Strips a generic wrapper like `Option` from a type.
+fn strip_type<'a>(rust_type: &'a str, wrapper: &str) -> &'a str {
+    rust_type.strip_prefix(wrapper)
+        .and_then(|it| it.strip_prefix('<'))
+        .and_then(|it| it.strip_suffix('>'))
+        .unwrap_or(rust_type)
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+    "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "abstract", "become", "box", "do", "final", "macro", "override",
+    "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Converts a property or parameter name into a snake case identifier,
+/// keywords are escaped as raw identifiers.
+fn field_name(name: &str) -> String {
+    let ident = snake_ident(name);
+    match ident.as_str() {
+        "" => String::from("value"),
+        // these can't be raw identifiers
+        "self" | "super" | "crate" => format!("{}_", ident),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", it),
+        it if KEYWORDS.contains(&it) => format!("r#{}", it),
+        _ => ident,
+    }
+}
+
+/// Converts an enum value into a variant name, e.g. `per-stay` to `PerStay`.
+fn variant_name(value: &str) -> String {
+    let ident = pascal_ident(value);
+    match ident.as_str() {
+        "" if value.starts_with('-') => String::from("Minus"),
+        "" => String::from("Empty"),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("Value{}", it),
+        // a variant named Self can't be declared
+        "Self" => String::from("Self_"),
+        _ => ident,
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type")]
+enum Model {
+    Struct {
+        name: String,
+        description: Option<String>,
+        fields: Vec<Field>,
+        /// Type of the values of a flattened map for additional properties
+        additional: Option<String>,
+    },
+    Enum {
+        name: String,
+        description: Option<String>,
+        variants: Vec<EnumVariant>,
+    },
+    Union {
+        name: String,
+        description: Option<String>,
+        /// The discriminator property, untagged if there is none
+        tag: Option<String>,
+        variants: Vec<UnionVariant>,
+    },
+    Alias {
+        name: String,
+        description: Option<String>,
+        alias: String,
+    },
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Field {
+    name: String,
+    /// The json name if it differs from the identifier
+    rename: Option<String>,
+    field_type: String,
+    optional: bool,
+    flatten: bool,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct EnumVariant {
+    name: String,
+    value: String,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct UnionVariant {
+    name: String,
+    /// The discriminator value if it differs from the name
+    rename: Option<String>,
+    variant_type: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Param {
+    name: String,
+    ident: String,
+    /// The type of the argument, references for types that aren't copied
+    arg_type: String,
+    required: bool,
+    is_array: bool,
+    /// Whether the value or items can be formatted, others are sent as json
+    display: bool,
+    description: Option<String>,
+    /// The owned type of the value
+    param_type: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Body {
+    body_type: String,
+    required: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ErrorVariant {
+    name: String,
+    status: String,
+    /// Pattern matching the status code
+    status_match: String,
+    with_status: bool,
+    body_type: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Operation {
+    name: String,
+    error_type: String,
+    summary: Option<String>,
+    deprecated: bool,
+    method: String,
+    /// The path with `{}` in place of the path params
+    url_format: String,
+    path_params: Vec<Param>,
+    query_params: Vec<Param>,
+    header_params: Vec<Param>,
+    body: Option<Body>,
+    accept: Option<String>,
+    /// How the response body is read: json, text, bytes, stream or empty
+    response_kind: String,
+    return_type: String,
+    errors: Vec<ErrorVariant>,
+    /// Whether a default response catches the undocumented statuses
+    has_default: bool,
+    security: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct HandlerResponse {
+    name: String,
+    status: String,
+    /// The status code unless the response covers a range or is the default
+    code: Option<u16>,
+    /// How the body is written: json, text, bytes, stream or empty
+    kind: String,
+    body_type: Option<String>,
+    media_type: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Handler {
+    name: String,
+    /// Prefix of the types of the operation
+    type_name: String,
+    reply_type: String,
+    summary: Option<String>,
+    deprecated: bool,
+    path_params: Vec<Param>,
+    query_params: Vec<Param>,
+    header_params: Vec<Param>,
+    body: Option<Body>,
+    responses: Vec<HandlerResponse>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Route {
+    path: String,
+    /// Lowercase methods with the names of their handlers
+    methods: Vec<(String, String)>,
+}
+
+#[derive(Serialize)]
+struct CargoFile {
+    name: String,
+    description: String,
+}
+
+impl TemplateContext for CargoFile {
+    fn template(&self) -> &'static str { "cargo.tera" }
+    fn filename(&self) -> String { String::from("Cargo.toml") }
+}
+
+#[derive(Serialize)]
+struct LibFile {}
+impl TemplateContext for LibFile {
+    fn template(&self) -> &'static str { "lib.tera" }
+    fn filename(&self) -> String { String::from("lib.rs") }
+}
+
+#[derive(Serialize)]
+struct ModelsFile {
+    models: Vec<Model>,
+}
+
+impl TemplateContext for ModelsFile {
+    fn template(&self) -> &'static str { "models.tera" }
+    fn filename(&self) -> String { String::from("models.rs") }
+}
+
+#[derive(Serialize)]
+struct ClientFile {
+    schemes: Vec<models::SecurityScheme>,
+    /// Server urls with the defaults of their variables
+    servers: Vec<String>,
+}
+
+impl TemplateContext for ClientFile {
+    fn template(&self) -> &'static str { "client.tera" }
+    fn filename(&self) -> String { String::from("client.rs") }
+}
+
+#[derive(Serialize)]
+struct ApisFile {
+    modules: Vec<String>,
+}
+
+impl TemplateContext for ApisFile {
+    fn template(&self) -> &'static str { "apis.tera" }
+    fn filename(&self) -> String { String::from("mod.rs") }
+}
+
+#[derive(Serialize)]
+struct ApiFile {
+    module: String,
+    operations: Vec<Operation>,
+}
+
+impl TemplateContext for ApiFile {
+    fn template(&self) -> &'static str { "api.tera" }
+    fn filename(&self) -> String { format!("{}.rs", self.module) }
+}
+
+#[derive(Serialize)]
+struct ServerLibFile {}
+impl TemplateContext for ServerLibFile {
+    fn template(&self) -> &'static str { "lib.tera" }
+    fn filename(&self) -> String { String::from("lib.rs") }
+}
+
+#[derive(Serialize)]
+struct ServerApiFile {
+    handlers: Vec<Handler>,
+}
+
+impl TemplateContext for ServerApiFile {
+    fn template(&self) -> &'static str { "api.tera" }
+    fn filename(&self) -> String { String::from("api.rs") }
+}
+
+#[derive(Serialize)]
+struct RouterFile {
+    routes: Vec<Route>,
+    handlers: Vec<Handler>,
+}
+
+impl TemplateContext for RouterFile {
+    fn template(&self) -> &'static str { "router.tera" }
+    fn filename(&self) -> String { String::from("router.rs") }
+}
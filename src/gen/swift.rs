@@ -0,0 +1,307 @@
+use serde_derive::Serialize;
+use crate::{
+    specs::{OpenApiSpec, SchemaSpec, RefOr},
+    gen::{
+        Generator, Options, TemplateContext,
+        models::{
+            self, Language, schema_type, non_empty, discriminator_tags, all_of_properties,
+            type_name, camel_ident,
+        },
+    },
+    util,
+};
+
+/// Generates `Codable` structs for all schemas, enums with raw values and
+/// enums with associated values for unions.
+pub struct SwiftGenerator;
+impl Generator for SwiftGenerator {
+    fn generate(spec: &OpenApiSpec, _options: &Options) {
+        let templates = util::load_templates("swift").unwrap();
+
+        let mut builder = ModelBuilder::new(spec.components.as_ref(), Swift);
+        builder.add_schemas();
+
+        let models = builder.models;
+        let file = ModelsFile {
+            uses_json: models.iter().any(|it| it.uses_json()),
+            models,
+        };
+
+        util::write_templates(&templates, &vec![file], None).unwrap();
+    }
+}
+
+/// The swift side of the models. Intersections are flattened into structs
+/// and unions are enums with associated values.
+struct Swift;
+
+type ModelBuilder<'a> = models::ModelBuilder<'a, Swift>;
+
+impl Language for Swift {
+    type Model = Model;
+
+    const ANY: &'static str = "JSONValue";
+
+    fn primitive_type(spec: &SchemaSpec) -> String {
+        let swift_type = match (schema_type(spec), spec.format.as_deref()) {
+            ("string", _) => "String",
+            ("integer", Some("int32")) => "Int32",
+            ("integer", Some("int64")) => "Int64",
+            ("integer", _) => "Int",
+            ("number", Some("float")) => "Float",
+            ("number", _) => "Double",
+            ("boolean", _) => "Bool",
+            _ => Self::ANY,
+        };
+
+        String::from(swift_type)
+    }
+
+    fn array_type(items: &str) -> String {
+        format!("[{}]", items)
+    }
+
+    fn map_type(values: Option<String>) -> String {
+        format!("[String: {}]", values.as_deref().unwrap_or(Self::ANY))
+    }
+
+    fn alias(name: String, description: Option<String>, alias: String) -> Model {
+        Model::Alias { name, description, alias }
+    }
+
+    fn enum_type(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let values: Vec<&serde_json::Value> = spec.schema_enum.iter()
+            .filter(|it| !it.is_null())
+            .collect();
+
+        let base = if !values.is_empty() && values.iter().all(|it| it.is_string()) {
+            "String"
+        } else if !values.is_empty() && values.iter().all(|it| it.is_i64() || it.is_u64()) {
+            "Int"
+        } else {
+            return Self::primitive_type(spec);
+        };
+
+        let mut variants: Vec<EnumVariant> = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let text = match value.as_str() {
+                Some(it) => String::from(it),
+                None => value.to_string(),
+            };
+            let member = spec.enum_var_names.get(i)
+                .map_or_else(|| case_name(&text), |it| case_name(it));
+
+            let mut unique = member.clone();
+            let mut suffix = 1;
+            while variants.iter().any(|it| it.name == unique) {
+                suffix += 1;
+                unique = format!("{}{}", member, suffix);
+            }
+
+            // string raw values default to the name of the case
+            let raw_value = match value.as_str() {
+                Some(it) if it == unique.trim_matches('`') => None,
+                _ => Some(value.to_string()),
+            };
+
+            variants.push(EnumVariant {
+                name: unique,
+                raw_value,
+                description: spec.enum_descriptions.get(i).filter(|it| !it.is_empty()).cloned(),
+            });
+        }
+
+        builder.models.push(Model::Enum {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            base: String::from(base),
+            variants,
+        });
+
+        String::from(name)
+    }
+
+    /// The parts of an intersection are flattened into one struct.
+    fn object(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let (properties, required) = all_of_properties(spec, builder.components);
+        let mut fields = vec![];
+        for (property, property_spec) in properties.iter() {
+            let property_type = builder.type_of(property_spec, &format!("{}{}", name, type_name(property)));
+            let required = required.contains(property);
+            let nullable = builder.is_nullable(property_spec);
+
+            let ident = property_name(property);
+            fields.push(Field {
+                key: Some(property.as_str())
+                    .filter(|it| *it != ident.trim_matches('`'))
+                    .map(|it| serde_json::Value::from(it).to_string()),
+                name: ident,
+                field_type: if !required || nullable { format!("{}?", property_type) } else { property_type },
+                optional: !required || nullable,
+                description: builder.description(property_spec),
+            });
+        }
+
+        builder.models.push(Model::Struct {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            reference: is_cyclic(builder, name),
+            fields,
+        });
+
+        String::from(name)
+    }
+
+    /// Unions are enums with a case per variant. A discriminator decides the
+    /// case to decode, otherwise the first variant that decodes is used.
+    fn union(builder: &mut ModelBuilder, spec: &SchemaSpec, name: &str) -> String {
+        let parts = if spec.one_of.is_empty() { &spec.any_of } else { &spec.one_of };
+        let tags = discriminator_tags(spec);
+
+        let mut variants: Vec<UnionVariant> = vec![];
+        for (i, part) in parts.iter().enumerate() {
+            let case = match part {
+                RefOr::Ref { ref ref_path } => property_name(ref_path.rsplit('/').next().unwrap_or_default()),
+                RefOr::Object(_) => format!("variant{}", i + 1),
+            };
+
+            let variant_type = builder.type_of(part, &format!("{}Variant{}", name, i + 1));
+            variants.push(UnionVariant {
+                name: case,
+                variant_type,
+                tag: tags.as_ref().map(|it| serde_json::Value::from(it[i].1.as_str()).to_string()),
+            });
+        }
+
+        builder.models.push(Model::Union {
+            name: String::from(name),
+            description: non_empty(&spec.description),
+            indirect: is_cyclic(builder, name),
+            tag: spec.discriminator.as_ref()
+                .filter(|_| tags.is_some())
+                .map(|it| serde_json::Value::from(it.property_name.as_str()).to_string()),
+            variants,
+        });
+
+        String::from(name)
+    }
+}
+
+/// Whether a model is part of a cycle. Structs can't contain themselves, so
+/// these become classes and indirect enums.
+fn is_cyclic(builder: &ModelBuilder, name: &str) -> bool {
+    builder.cyclic.iter().any(|it| type_name(it) == name)
+}
+
+const KEYWORDS: &[&str] = &[
+    "associatedtype", "class", "deinit", "enum", "extension", "fileprivate", "func", "import",
+    "init", "inout", "internal", "let", "open", "operator", "private", "protocol", "public",
+    "rethrows", "static", "struct", "subscript", "typealias", "var", "break", "case",
+    "continue", "default", "defer", "do", "else", "fallthrough", "for", "guard", "if", "in",
+    "repeat", "return", "switch", "where", "while", "as", "catch", "false", "is", "nil",
+    "super", "self", "throw", "throws", "true", "try",
+];
+
+fn property_name(name: &str) -> String {
+    let ident = camel_ident(name);
+    match ident.as_str() {
+        "" => String::from("value"),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", it),
+        it if KEYWORDS.contains(&it) => format!("`{}`", it),
+        _ => ident,
+    }
+}
+
+fn case_name(value: &str) -> String {
+    let ident = camel_ident(value);
+    match ident.as_str() {
+        "" if value.starts_with('-') => String::from("minus"),
+        "" => String::from("empty"),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) && value.starts_with('-') => format!("minus{}", it),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("value{}", it),
+        it if KEYWORDS.contains(&it) => format!("`{}`", it),
+        _ => ident,
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type")]
+enum Model {
+    Struct {
+        name: String,
+        description: Option<String>,
+        /// Whether it is a final class, which is needed for cycles
+        reference: bool,
+        fields: Vec<Field>,
+    },
+    Enum {
+        name: String,
+        description: Option<String>,
+        /// The raw value type, `String` or `Int`
+        base: String,
+        variants: Vec<EnumVariant>,
+    },
+    Union {
+        name: String,
+        description: Option<String>,
+        indirect: bool,
+        /// The discriminator property as literal, variants are tried in order
+        /// if there is none
+        tag: Option<String>,
+        variants: Vec<UnionVariant>,
+    },
+    Alias {
+        name: String,
+        description: Option<String>,
+        alias: String,
+    },
+}
+
+impl Model {
+    fn uses_json(&self) -> bool {
+        match self {
+            Model::Struct { ref fields, .. } => fields.iter().any(|it| it.field_type.contains("JSONValue")),
+            Model::Union { ref variants, .. } => variants.iter().any(|it| it.variant_type.contains("JSONValue")),
+            Model::Alias { ref alias, .. } => alias.contains("JSONValue"),
+            Model::Enum { .. } => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Field {
+    name: String,
+    /// The coding key as literal if it differs from the property name
+    key: Option<String>,
+    field_type: String,
+    /// Whether the initializer defaults it to nil
+    optional: bool,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct EnumVariant {
+    name: String,
+    /// The raw value as literal if it differs from the name
+    raw_value: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct UnionVariant {
+    name: String,
+    variant_type: String,
+    /// The discriminator value as literal
+    tag: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ModelsFile {
+    uses_json: bool,
+    models: Vec<Model>,
+}
+
+impl TemplateContext for ModelsFile {
+    fn template(&self) -> &'static str { "models.tera" }
+    fn filename(&self) -> String { String::from("Models.swift") }
+}
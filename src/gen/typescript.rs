@@ -1,108 +1,393 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use serde_derive::Serialize;
+use tera::Tera;
 use voca_rs::case;
 use std::fmt::Debug;
 use crate::{
-    specs::{OpenApiSpec, SchemaSpec, RefOr, PathSpec, TagSpec, OperationSpec},
-    gen::{Generator, TemplateContext},
+    specs::{
+        self, OpenApiSpec, SchemaSpec, RefOr, AdditionalPropertiesSpec, PathSpec, TagSpec, OperationSpec,
+        ResponseSpec, ComponentsSpec,  SecurityRequirementSpec,
+        ServerSpec,
+    },
+    gen::{Generator, Options, TemplateContext, IndexFile},
     util,
 };
+use super::models::{self, schema_type, camel_ident, pascal_ident};
+
+/// The http client the generated services are built on. Each one has its
+/// own template directory, the models come from the shared `typescript` one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Client {
+    Angular,
+    Fetch,
+    /// The fetch client along with react query hooks
+    ReactQuery,
+    Axios,
+}
+
+impl Client {
+    fn templates(self) -> &'static str {
+        match self {
+            Client::Angular => "angular-client",
+            Client::Fetch => "fetch-client",
+            Client::ReactQuery => "react-query",
+            Client::Axios => "axios-client",
+        }
+    }
+
+    /// Template directories the client builds on
+    fn shared_templates(self) -> &'static [&'static str] {
+        match self {
+            Client::Angular | Client::Fetch | Client::Axios => &["typescript"],
+            Client::ReactQuery => &["fetch-client", "typescript"],
+        }
+    }
+}
 
 pub struct TypescriptGenerator;
 impl Generator for TypescriptGenerator {
-    fn generate(spec: &OpenApiSpec) {
-        let templates = util::load_templates("angular-client").unwrap();
-
-        let schema_models = spec.components.as_ref()
-            .map_or_else(|| vec![], |it| generate_models(&it.schemas));
-        let schema_files: Vec<String> = schema_models.iter()
-            .map(|it| it.filename()).collect();
-
-        if !schema_files.is_empty() {
-            let schema_index = IndexFile {
-                exports: schema_files.iter()
-                    .map(|it| it.trim_end_matches(".ts").to_owned())
-                    .collect(),
-            };
+    fn generate(spec: &OpenApiSpec, options: &Options) {
+        generate_client(spec, options, Client::Angular);
+    }
+}
 
-            util::write_templates(&templates, &schema_models, Some("schemas")).unwrap();
-            util::write_templates(&templates, &vec![schema_index], Some("schemas")).unwrap();
-        }
+/// Generates a dependency free client on top of `fetch` with the same models
+/// as the angular client.
+pub struct FetchGenerator;
+impl Generator for FetchGenerator {
+    fn generate(spec: &OpenApiSpec, options: &Options) {
+        generate_client(spec, options, Client::Fetch);
+    }
+}
 
-        let response_models = spec.components.as_ref().map_or_else(|| vec![], |it| {
-            // discard everything that doesn't contain a json body
-            let mut response_specs = BTreeMap::new();
-            it.responses.iter()
-                .filter_map(|(name, spec)| {
-                    if let Some(spec) = spec.maybe_map_cloned(|it| {
-                        it.content.get("application/json").map(|it| it.schema.clone())
-                    }) { Some((name.clone(), spec)) } else { None }
-                })
-                .for_each(|(name, spec)| { response_specs.insert(name, spec); });
+/// Generates tanstack query hooks for every operation on top of the fetch
+/// client, one file per tag like the services.
+pub struct ReactQueryGenerator;
+impl Generator for ReactQueryGenerator {
+    fn generate(spec: &OpenApiSpec, options: &Options) {
+        generate_client(spec, options, Client::ReactQuery);
+    }
+}
+
+/// Generates a client with a class per tag that sends its requests through
+/// an injected axios instance.
+pub struct AxiosGenerator;
+impl Generator for AxiosGenerator {
+    fn generate(spec: &OpenApiSpec, options: &Options) {
+        generate_client(spec, options, Client::Axios);
+    }
+}
 
-            generate_models(&response_specs)
+fn generate_client(spec: &OpenApiSpec, options: &Options, client: Client) {
+    let templates = util::load_templates_with(client.templates(), client.shared_templates()).unwrap();
+
+    let cycles = spec.components.as_ref()
+        .map_or_else(Cycles::default, |it| Cycles {
+            aliases: alias_cycles(it, false),
+            maps: alias_cycles(it, true),
         });
-        let response_files: Vec<String> = response_models.iter()
-            .map(|it| it.filename()).collect();
 
-        if !response_files.is_empty() {
-            let response_index = IndexFile {
-                exports: response_files.iter()
-                    .map(|it| it.trim_end_matches(".ts").to_owned())
+    let mut schema_models = spec.components.as_ref()
+        .map_or_else(|| vec![], |it| generate_models(&it.schemas, options));
+    break_cycles(&mut schema_models, "schemas", &cycles);
+    let enums = nullable_enums(&schema_models);
+    let mut converting = HashSet::new();
+
+    let writable = writable_variants(&schema_models);
+    for file in schema_models.iter_mut() {
+        if writable.contains(&file.root.name()) {
+            let name = format!("Writable{}", file.root.name());
+            let (model, imports) = file.writable_model(&name, &writable);
+            file.writable = Some(model);
+            file.add_imports(imports);
+        }
+    }
+    write_models(&templates, &mut schema_models, "schemas", &enums, &mut converting, options);
+
+    let mut response_models = spec.components.as_ref().map_or_else(|| vec![], |it| {
+        // discard everything that doesn't contain a json body
+        let mut response_specs = BTreeMap::new();
+        it.responses.iter()
+            .filter_map(|(name, spec)| {
+                if let Some(spec) = spec.maybe_map_cloned(|it| {
+                    specs::json_schema(&it.content)
+                }) { Some((name.clone(), spec)) } else { None }
+            })
+            .for_each(|(name, spec)| { response_specs.insert(name, spec); });
+
+        let mut response_models = generate_models(&response_specs, options);
+        break_cycles(&mut response_models, "responses", &cycles);
+        response_models
+    });
+    write_models(&templates, &mut response_models, "responses", &enums, &mut converting, options);
+
+    let mut request_models = spec.components.as_ref().map_or_else(|| vec![], |it| {
+        // discard everything that doesn't contain a json body
+        let mut request_specs = BTreeMap::new();
+        it.request_bodies.iter()
+            .filter_map(|(name, spec)| {
+                if let Some(spec) = spec.maybe_map_cloned(|it| {
+                    specs::json_schema(&it.content)
+                }) { Some((name.clone(), spec)) } else { None }
+            })
+            .for_each(|(name, spec)| { request_specs.insert(name, spec); });
+
+        let mut request_models = generate_models(&request_specs, options);
+        break_cycles(&mut request_models, "requestBodies", &cycles);
+
+        // request bodies are only ever sent, so they use the writable
+        // variants directly
+        for file in request_models.iter_mut() {
+            let (model, imports) = file.writable_model(&file.root.name(), &writable);
+            file.root = model;
+            file.add_imports(imports);
+        }
+
+        request_models
+    });
+    write_models(&templates, &mut request_models, "request-bodies", &enums, &mut converting, options);
+
+    let context = EndpointContext {
+        components: spec.components.as_ref(),
+        security: &spec.security,
+        writable: &writable,
+        options,
+    };
+
+    let mut error_specs = BTreeMap::new();
+    let mut body_specs = BTreeMap::new();
+    let mut services = generate_services(
+        &case::kebab_case(&spec.info.title),
+        &spec.tags, &spec.paths,
+        &context,
+        &mut error_specs,
+        &mut body_specs,
+    );
+
+    // inline error and success bodies, the referenced ones are generated above
+    let mut error_models = generate_models(&error_specs, options);
+    write_models(&templates, &mut error_models, "errors", &enums, &mut converting, options);
+    let mut body_models = generate_models(&body_specs, options);
+    write_models(&templates, &mut body_models, "response-bodies", &enums, &mut converting, options);
+    for endpoint in services.iter_mut().flat_map(|it| it.endpoints.iter_mut()) {
+        endpoint.prune_conversions(&converting);
+    }
+
+    let service_files = services.iter().map(|it| it.filename()).collect();
+    let service_index = IndexFile { exports: service_files };
+
+    util::write_templates(&templates, &services, Some("services")).unwrap();
+    util::write_templates(&templates, &vec![service_index], Some("services")).unwrap();
+
+    if client == Client::ReactQuery {
+        let hooks: Vec<HooksFile> = services.iter()
+            .map(|it| HooksFile {
+                tag: case::camel_case(it.name.trim_end_matches("Service")),
+                service_file: it.filename().trim_end_matches(".ts").to_owned(),
+                service: it,
+                // streams don't fit into a cache of single results
+                hooks: it.endpoints.iter()
+                    .filter(|it| it.response_type != ResponseType::EventStream)
+                    .map(|it| Hook {
+                        name: pascal_ident(&it.name),
+                        query: it.method == "get",
+                        has_params: it.body_param.is_some() || it.accept.len() > 1
+                            || !it.path_params.is_empty() || !it.query_params.is_empty()
+                            || !it.header_params.is_empty(),
+                        params_optional: it.body_param.is_none() && it.path_params.is_empty()
+                            && !it.query_params.iter().chain(&it.header_params).any(|it| it.required),
+                        endpoint: it,
+                    })
                     .collect(),
-            };
+            })
+            .collect();
 
-            util::write_templates(&templates, &response_models, Some("responses")).unwrap();
-            util::write_templates(&templates, &vec![response_index], Some("responses")).unwrap();
-        }
+        let context = HooksContextFile {
+            client_name_kebab: case::kebab_case(&spec.info.title),
+            client_name_pascal: case::pascal_case(&spec.info.title),
+        };
+
+        let mut hooks_index = IndexFile {
+            exports: hooks.iter().map(|it| it.filename().trim_end_matches(".ts").to_owned()).collect(),
+        };
+        hooks_index.exports.push(context.filename().trim_end_matches(".ts").to_owned());
+
+        util::write_templates(&templates, &hooks, Some("hooks")).unwrap();
+        util::write_templates(&templates, &vec![context], Some("hooks")).unwrap();
+        util::write_templates(&templates, &vec![hooks_index], Some("hooks")).unwrap();
+    }
+
+    let config = ClientConfigFile {
+        client_name_kebab: case::kebab_case(&spec.info.title),
+        client_name_pascal: case::pascal_case(&spec.info.title),
+        security_schemes: spec.components.as_ref()
+            .map_or_else(Vec::new, generate_security_schemes),
+    };
+
+    util::write_templates(&templates, &vec![config], None).unwrap();
+    util::write_templates(&templates, &vec![UtilFile {}], None).unwrap();
+
+    if options.validators {
+        util::write_templates(&templates, &vec![ValidationUtilFile {}], None).unwrap();
+    }
+
+    let servers = if spec.servers.is_empty() {
+        vec![Server::from(&ServerSpec::root())]
+    } else {
+        spec.servers.iter().map(Server::from).collect()
+    };
+
+    let servers_file = ServersFile {
+        servers,
+        operation_servers: services.iter()
+            .flat_map(|it| it.endpoints.iter())
+            .filter(|it| !it.servers.is_empty())
+            .map(|it| (it.name.clone(), it.servers.clone()))
+            .collect(),
+    };
 
-        let request_models = spec.components.as_ref().map_or_else(|| vec![], |it| {
-            // discard everything that doesn't contain a json body
-            let mut request_specs = BTreeMap::new();
-            it.request_bodies.iter()
-                .filter_map(|(name, spec)| {
-                    if let Some(spec) = spec.maybe_map_cloned(|it| {
-                        it.content.get("application/json").map(|it| it.schema.clone())
-                    }) { Some((name.clone(), spec)) } else { None }
+    util::write_templates(&templates, &vec![servers_file], None).unwrap();
+
+    // the other clients throw typed errors on their own and have no module
+    if client == Client::Angular {
+        let module = ClientModuleFile {
+            client_name_kebab: case::kebab_case(&spec.info.title),
+            client_name_pascal: case::pascal_case(&spec.info.title),
+            services: services.iter()
+                .map(|it| ServiceImport {
+                    name: it.name.clone(),
+                    file: it.filename().trim_end_matches(".ts").to_owned(),
                 })
-                .for_each(|(name, spec)| { request_specs.insert(name, spec); });
+                .collect(),
+            interceptor: options.interceptor,
+        };
 
-            generate_models(&request_specs)
-        });
-        let request_files: Vec<String> = request_models.iter()
-            .map(|it| it.filename()).collect();
+        util::write_templates(&templates, &vec![module], None).unwrap();
+    }
 
-        if !request_files.is_empty() {
-            let request_index = IndexFile {
-                exports: request_files.iter()
-                    .map(|it| it.trim_end_matches(".ts").to_owned())
-                    .collect(),
-            };
+    if client == Client::Angular && options.interceptor {
+        let interceptor = InterceptorFile {
+            client_name_kebab: case::kebab_case(&spec.info.title),
+            client_name_pascal: case::pascal_case(&spec.info.title),
+        };
 
-            util::write_templates(&templates, &request_models, Some("request-bodies")).unwrap();
-            util::write_templates(&templates, &vec![request_index], Some("request-bodies")).unwrap();
-        }
+        util::write_templates(&templates, &vec![interceptor], None).unwrap();
+    }
+}
 
-        let server = spec.servers.iter().nth(0).expect("no servers");
-        let services = generate_services(
-            &case::kebab_case(&spec.info.title),
-            &server.url,
-            &spec.tags, &spec.paths,
-        );
+/// Writes the model files of a directory along with their index and, if
+/// enabled, their validation modules. The models that convert something are
+/// added to `converting` for the directories written after.
+fn write_models(
+    templates: &Tera,
+    files: &mut [ModelFile],
+    dir: &str,
+    enums: &HashSet<String>,
+    converting: &mut HashSet<String>,
+    options: &Options,
+) {
+    if files.is_empty() {
+        return;
+    }
+
+    prune_conversions(files, converting);
+    for file in files.iter_mut() {
+        file.remove_local_imports(dir);
+        file.add_enum_nulls(enums);
+    }
+
+    let mut index = IndexFile {
+        exports: files.iter()
+            .map(|it| it.filename().trim_end_matches(".ts").to_owned())
+            .collect(),
+    };
+
+    if options.validators {
+        let validation_files: Vec<ValidationFile> = files.iter()
+            .map(|it| ValidationFile { model: it.clone() })
+            .collect();
+
+        index.exports.extend(validation_files.iter()
+            .map(|it| it.filename().trim_end_matches(".ts").to_owned()));
+        util::write_templates(templates, &validation_files, Some(dir)).unwrap();
+    }
+
+    util::write_templates(templates, &files.to_vec(), Some(dir)).unwrap();
+    util::write_templates(templates, &vec![index], Some(dir)).unwrap();
+}
+
+/// Collects the models that have dates in them, directly or through the
+/// models they reference, and drops the conversions of all others. Only the
+/// former get converters.
+fn prune_conversions(files: &mut [ModelFile], converting: &mut HashSet<String>) {
+    loop {
+        let added: Vec<String> = files.iter()
+            .flat_map(|it| it.named_models())
+            .filter(|(name, model)| !converting.contains(name) && model.converts(converting))
+            .map(|(name, _)| name)
+            .collect();
+
+        if added.is_empty() {
+            break;
+        }
+        converting.extend(added);
+    }
 
-        let service_files = services.iter().map(|it| it.filename()).collect();
-        let service_index = IndexFile { exports: service_files };
+    for file in files.iter_mut() {
+        let models = std::iter::once(&mut file.root)
+            .chain(file.writable.iter_mut())
+            .chain(file.nested.values_mut().flatten());
 
-        util::write_templates(&templates, &services, Some("services")).unwrap();
-        util::write_templates(&templates, &vec![service_index], Some("services")).unwrap();
+        for model in models {
+            match model {
+                Model::Struct { ref mut fields, .. } => {
+                    for field in fields.iter_mut() {
+                        field.prune_conversion(converting);
+                    }
+                },
+                Model::Alias { ref mut conversion, .. } => {
+                    *conversion = conversion.as_ref().and_then(|it| it.prune(converting));
+                },
+                Model::Enum { .. } => {},
+            }
+        }
     }
 }
 
+fn generate_security_schemes(components: &ComponentsSpec) -> Vec<SecurityScheme> {
+    models::security_schemes(components, |it| String::from(it)).into_iter()
+        .map(|it| {
+            let models::SecurityScheme { name, description, .. } = it;
+            match it.kind.as_str() {
+                "basic" => SecurityScheme::Basic { name, description },
+                "bearer" => SecurityScheme::Bearer { name, description },
+                "http" => SecurityScheme::Http { name, description, scheme: it.param_name },
+                kind => SecurityScheme::ApiKey {
+                    name, description,
+                    param_name: it.param_name,
+                    location: String::from(kind.trim_start_matches("api_key_")),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Spec wide information that is needed while generating endpoints.
+struct EndpointContext<'a> {
+    components: Option<&'a ComponentsSpec>,
+    security: &'a [SecurityRequirementSpec],
+    /// Schemas that have a writable variant for request bodies
+    writable: &'a HashSet<String>,
+    options: &'a Options,
+}
+
 fn generate_services(
     client_name: &str,
-    base_path: &str,
     tags: &Vec<TagSpec>,
     paths: &BTreeMap<String, PathSpec>,
+    context: &EndpointContext,
+    error_specs: &mut BTreeMap<String, RefOr<SchemaSpec>>,
+    body_specs: &mut BTreeMap<String, RefOr<SchemaSpec>>,
 ) -> Vec<ServiceFile> {
     let mut tag_map = HashMap::new();
 
@@ -121,7 +406,10 @@ fn generate_services(
         if let Some(ref trace_op) = spec.trace { ops.push(("trace", trace_op.clone())); }
         if let Some(ref options_op) = spec.options { ops.push(("options", options_op.clone())); }
 
-        insert_endpoints(&mut tag_map, &path, &ops);
+        insert_endpoints(
+            &mut tag_map, &path, spec, &ops,
+            context, error_specs, body_specs,
+        );
     }
 
     let client_name_kebab = case::kebab_case(client_name);
@@ -147,7 +435,6 @@ fn generate_services(
                 name: format!("{}Service", case::pascal_case(&tag_name)),
                 client_name_kebab: client_name_kebab.clone(),
                 client_name_pascal: client_name_pascal.clone(),
-                base_path: String::from(base_path),
                 endpoints, imports,
                 models,
                 nested: HashMap::new(),
@@ -159,24 +446,40 @@ fn generate_services(
 fn insert_endpoints(
     tags: &mut HashMap<String, (Vec<Endpoint>, Vec<Model>, Vec<Import>)>,
     path: &str,
+    path_spec: &PathSpec,
     ops: &Vec<(&str, OperationSpec)>,
+    context: &EndpointContext,
+    error_specs: &mut BTreeMap<String, RefOr<SchemaSpec>>,
+    body_specs: &mut BTreeMap<String, RefOr<SchemaSpec>>,
 ) {
+    let components = context.components;
+
     for (method, spec) in ops {
         if spec.tags.is_empty() {
             println!("\tskipping untagged operation: {} ({})", spec.operation_id, method);
             continue;
         }
 
+        if let Some(media_type) = unsupported_body(spec, components) {
+            println!(
+                "\tskipping {} ({}), {} request bodies are not supported",
+                spec.operation_id, method, media_type,
+            );
+            continue;
+        }
+
+        // operations may be tagged with tags the spec doesn't declare
         let (ref mut endpoints, ref mut models, ref mut imports) = tags
-            .get_mut(&spec.tags[0]).expect("tag not found");
+            .entry(spec.tags[0].clone()).or_default();
 
-        let request_body = spec.request_body.clone().map(|request_spec| {
+        let request_spec = spec.request_body.as_ref()
+            .and_then(|it| it.maybe_map_cloned(|it| specs::json_schema(&it.content)));
+        let request_body = request_spec.map(|request_spec| {
             let (request_models, request_imports) = generate_model(
                 &format!("{}Request", case::pascal_case(&spec.operation_id)),
-                &request_spec.maybe_map_cloned(|it| {
-                    it.content.get("application/json").map(|it| it.schema.clone())
-                }).expect("no json body!"),
+                &request_spec,
                 None,
+                context.options,
             );
 
             let root = if !request_models.is_empty() {
@@ -185,17 +488,41 @@ fn insert_endpoints(
                 panic!("no models generated for request body");
             };
 
+            // bodies that reference a schema directly need its writable variant,
+            // component request bodies are generated writable already
+            let writable_type = match root {
+                Model::Alias { ref alias, .. } if is_schema_ref(alias, &request_imports)
+                    && context.writable.contains(alias) => {
+                    Some(format!("Writable{}", alias))
+                },
+                _ => None,
+            };
+
             imports.extend(request_imports.into_iter());
             match root {
-                Model::Alias { ref alias, is_array, .. } => {
+                Model::Alias { ref alias, is_array, ref conversion, .. } => {
                     // TODO
                     // models.extend(request_models.into_iter().skip(1));
 
+                    if let Some(ref writable_type) = writable_type {
+                        imports.push(Import {
+                            import_type: writable_type.clone(),
+                            file: String::from("schemas"),
+                        });
+                    }
+
                     Field {
                         name: alias.clone(),
-                        field_type: alias.clone(),
+                        conversion: match writable_type {
+                            // writable models only have converters if dates are converted
+                            Some(ref name) if needs_converters(context.options) => Some(Conversion::Model { name: name.clone() }),
+                            Some(_) => None,
+                            None => conversion.clone(),
+                        },
+                        field_type: writable_type.unwrap_or_else(|| alias.clone()),
                         required: true, // TODO
                         is_array,
+                        ..Field::default()
                     }
                 },
                 _ => {
@@ -207,6 +534,7 @@ fn insert_endpoints(
                         field_type: root.name(),
                         required: true, // TODO
                         is_array: false,
+                        ..Field::default()
                     }
                 },
             }
@@ -215,12 +543,15 @@ fn insert_endpoints(
         let mut query_params = Vec::new();
         let mut path_params = Vec::new();
         let mut header_params = Vec::new();
+        let mut idents: Vec<String> = LOCALS.iter().map(|it| String::from(*it)).collect();
 
-        for param in spec.parameters.iter() {
+        for param in path_spec.params(spec) {
+            let ident = param_ident(&param.name, &param.location, &mut idents);
             let (param_models, param_imports) = generate_model(
                 &case::pascal_case(&param.name),
                 &param.schema,
                 Some(case::pascal_case(&spec.operation_id)),
+                context.options,
             );
 
             let root = if !param_models.is_empty() {
@@ -229,28 +560,48 @@ fn insert_endpoints(
                 panic!("no models generated for param");
             };
 
+            // services don't declare the inline models of params, so their
+            // types have to be spelled out
+            let inline_type = |type_name: &str| {
+                let model = param_models.iter()
+                    .find(|(namespace, model)| {
+                        namespace.as_ref().is_some_and(|it| format!("{}.{}", it, model.name()) == type_name)
+                    })
+                    .map(|(_, model)| model);
+                match model {
+                    None => String::from(type_name),
+                    Some(model) => literal_type(model).unwrap_or_else(|| {
+                        println!(
+                            "\tparameter {} of {} has an inline model, it is typed as unknown",
+                            param.name, spec.operation_id,
+                        );
+                        String::from("unknown")
+                    }),
+                }
+            };
+
             imports.extend(param_imports.into_iter());
             let root_field = match root {
-                Model::Alias { ref alias, is_array, .. } => {
-                    // TODO
-                    // models.extend(param_models.into_iter().skip(1));
-
+                Model::Alias { ref alias, is_array, ref conversion, .. } => {
                     Field {
                         name: param.name.clone(),
-                        field_type: alias.clone(),
+                        ident,
+                        field_type: inline_type(alias),
                         required: param.required,
                         is_array,
+                        conversion: conversion.clone(),
+                        ..Field::from_schema(&param.schema)
                     }
                 },
                 _ => {
-                    // TODO
-                    // models.extend(param_models.into_iter());
-
+                    let namespace = case::pascal_case(&spec.operation_id);
                     Field {
                         name: param.name.clone(),
-                        field_type: root.name(),
+                        ident,
+                        field_type: inline_type(&format!("{}.{}", namespace, root.name())),
                         required: param.required,
                         is_array: false,
+                        ..Field::from_schema(&param.schema)
                     }
                 },
             };
@@ -259,26 +610,332 @@ fn insert_endpoints(
                 "query" => query_params.push(root_field),
                 "header" => header_params.push(root_field),
                 "path" => path_params.push(root_field),
-                _ => panic!("unknown param location"),
+                other => println!("\tskipping {} parameter {} of {}", other, param.name, spec.operation_id),
+            }
+        }
+
+        for placeholder in specs::path_placeholders(path) {
+            if path_params.iter().any(|it: &Field| it.name == placeholder) {
+                continue;
+            }
+
+            println!("\t{} has no parameter for {{{}}}, it takes a string", spec.operation_id, placeholder);
+            path_params.push(Field {
+                name: String::from(placeholder),
+                ident: param_ident(placeholder, "path", &mut idents),
+                field_type: String::from("string"),
+                required: true,
+                ..Field::default()
+            });
+        }
+
+        let (accept, response_schema) = success_response(&spec.responses, components)
+            .unwrap_or_default();
+
+        let mut response_conversion = None;
+        let (response_type, return_type) = match (accept.first(), response_schema) {
+            (Some(media_type), Some(schema)) => {
+                let name = format!("{}Response", case::pascal_case(&spec.operation_id));
+                let (response_models, mut response_imports) = generate_model(
+                    &name,
+                    &schema,
+                    None,
+                    context.options,
+                );
+
+                // inline bodies that need models of their own are generated
+                // like inline error bodies, the return type refers to them
+                let is_model = match response_models.first() {
+                    Some((_, Model::Alias { .. })) => response_models.len() > 1,
+                    _ => true,
+                };
+                if is_model {
+                    body_specs.insert(name.clone(), schema.clone());
+                    response_imports.push(Import {
+                        import_type: name,
+                        file: String::from("response-bodies"),
+                    });
+                }
+
+                let return_type = match response_models.into_iter().next() {
+                    Some((_, Model::Alias { ref alias, is_array, ref conversion, .. })) => {
+                        // TODO
+                        // models.extend(response_models.into_iter().skip(1));
+
+                        response_conversion = conversion.clone().map(|it| Field {
+                            conversion: Some(it),
+                            is_array,
+                            ..Field::default()
+                        });
+                        if is_array { format!("Array<{}>", alias) } else { alias.clone() }
+                    },
+                    Some((_, root)) => {
+                        // TODO
+                        // models.extend(response_models.into_iter());
+
+                        root.name()
+                    },
+                    None => String::from("unknown"),
+                };
+
+                let response_type = ResponseType::from_media_type(media_type);
+                if response_type != ResponseType::Json && response_type != ResponseType::EventStream {
+                    response_conversion = None;
+                }
+
+                match response_type {
+                    // the schema only describes the payload format, the
+                    // client always receives it as a file or a string
+                    ResponseType::Blob => (response_type, String::from("Blob")),
+                    ResponseType::ArrayBuffer => (response_type, String::from("ArrayBuffer")),
+                    ResponseType::Text => (response_type, String::from("string")),
+                    _ => {
+                        imports.extend(response_imports);
+                        (response_type, return_type)
+                    },
+                }
+            },
+            (Some(media_type), None) => {
+                let response_type = ResponseType::from_media_type(media_type);
+                let return_type = String::from(response_type.body_type());
+                (response_type, return_type)
+            },
+            (None, _) => (ResponseType::Json, String::from("undefined")),
+        };
+
+        // media types the caller can choose that are read differently, the
+        // return type includes what they are read as
+        let alternatives: Vec<Alternative> = accept.iter()
+            .map(|it| Alternative {
+                media_type: it.clone(),
+                response_type: ResponseType::from_media_type(it),
+            })
+            .filter(|it| it.response_type != response_type)
+            .collect();
+        let mut return_type = return_type;
+        for alternative in alternatives.iter() {
+            let body_type = alternative.response_type.body_type();
+            if return_type != "unknown" && !return_type.split(" | ").any(|it| it == body_type) {
+                return_type = format!("{} | {}", return_type, body_type);
             }
         }
 
+        // the body is always serialized as json, so only json flavors can
+        // be offered to the caller
+        let mut content_types: Vec<String> = spec.request_body.as_ref()
+            .and_then(|it| it.resolve(components))
+            .map_or_else(Vec::new, |it| {
+                it.content.keys()
+                    .filter(|it| specs::is_json_media_type(it))
+                    .map(|it| specs::media_type_essence(it))
+                    .collect()
+            });
+        content_types.sort_by_key(|it| it != "application/json");
+
+        // each requirement is an alternative, empty ones allow anonymous
+        // access which is what happens anyway if no credentials match
+        let security = spec.security.as_ref().map_or(context.security, |it| it.as_slice())
+            .iter()
+            .filter(|it| !it.is_empty())
+            .map(|it| it.keys().cloned().collect())
+            .collect();
+
+        let has_success = spec.responses.keys().any(|it| it.starts_with('2'));
+        let errors = spec.responses.iter()
+            .filter(|(status, _)| {
+                status.starts_with('4') || status.starts_with('5')
+                    || (has_success && status.as_str() == "default")
+            })
+            .map(|(status, response)| {
+                let name = format!(
+                    "{}{}Error",
+                    case::pascal_case(&spec.operation_id),
+                    case::pascal_case(status),
+                );
+
+                ErrorResponse {
+                    // ranges like 4XX and the default response can't be
+                    // narrowed down to a single status
+                    status: if status.parse::<u16>().is_ok() {
+                        status.clone()
+                    } else {
+                        String::from("number")
+                    },
+                    body_type: error_body_type(
+                        &name, response, components, error_specs, imports,
+                    ),
+                }
+            })
+            .collect();
+
         endpoints.push(Endpoint {
             name: spec.operation_id.clone(),
+            ident: method_name(&spec.operation_id),
             body_param: request_body,
             path_params, query_params, header_params,
-            return_type: String::from("undefined"),
+            response_type, return_type, response_conversion,
+            accept, alternatives, content_types, security, errors,
+            // empty unless the path or operation overrides the servers
+            servers: if !spec.servers.is_empty() {
+                spec.servers.iter().map(Server::from).collect()
+            } else {
+                path_spec.servers.iter().map(Server::from).collect()
+            },
+            error_type: format!("{}Error", case::pascal_case(&spec.operation_id)),
             method: String::from(*method),
             path: String::from(path),
         });
     }
 }
 
-fn generate_models(model_specs: &BTreeMap<String, RefOr<SchemaSpec>>) -> Vec<ModelFile> {
+/// Names the services and hooks use for their own variables and options.
+const LOCALS: &[&str] = &[
+    "body", "options", "headers", "queryParams", "authorization", "response", "json",
+    "contentType", "accept", "observe", "reportProgress",
+];
+
+const KEYWORDS: &[&str] = &[
+    "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete",
+    "do", "else", "enum", "export", "extends", "false", "finally", "for", "function", "if",
+    "import", "in", "instanceof", "new", "null", "return", "super", "switch", "this", "throw",
+    "true", "try", "typeof", "var", "void", "while", "with", "let", "static", "yield", "await",
+];
+
+/// The method name of an operation, its id in camel case.
+fn method_name(operation_id: &str) -> String {
+    let ident = camel_ident(operation_id);
+    match ident.as_str() {
+        "" => String::from("operation"),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", it),
+        _ => ident,
+    }
+}
+
+/// The argument name of a parameter in camel case. Names that are taken by
+/// another parameter, a variable or a keyword get the location appended,
+/// e.g. `limitHeader`.
+fn param_ident(name: &str, location: &str, idents: &mut Vec<String>) -> String {
+    let ident = match camel_ident(name).as_str() {
+        "" => String::from("value"),
+        it if it.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", it),
+        it => String::from(it),
+    };
+
+    let mut unique = ident.clone();
+    let mut suffix = 1;
+    while idents.contains(&unique) || KEYWORDS.contains(&unique.as_str()) {
+        suffix += 1;
+        unique = match suffix {
+            2 => format!("{}{}", ident, case::capitalize(location, false)),
+            _ => format!("{}{}{}", ident, case::capitalize(location, false), suffix),
+        };
+    }
+
+    idents.push(unique.clone());
+    unique
+}
+
+/// Determines the type of an error response body. Inline schemas are
+/// collected into `error_specs` so they can be generated as separate models.
+fn error_body_type(
+    name: &str,
+    response: &RefOr<ResponseSpec>,
+    components: Option<&ComponentsSpec>,
+    error_specs: &mut BTreeMap<String, RefOr<SchemaSpec>>,
+    imports: &mut Vec<Import>,
+) -> String {
+    let schema = match response.resolve(components)
+        .and_then(|it| specs::json_schema(&it.content)) {
+        Some(it) => it,
+        None => return String::from("unknown"),
+    };
+
+    match (response, schema) {
+        // the whole response was generated as a model
+        (RefOr::Ref { ref ref_path }, _) |
+        (_, RefOr::Ref { ref ref_path }) => {
+            let (ref_type, import) = get_ref(ref_path);
+            imports.push(import);
+            ref_type
+        },
+        (_, schema) => {
+            error_specs.insert(String::from(name), schema);
+            imports.push(Import {
+                import_type: String::from(name),
+                file: String::from("errors"),
+            });
+
+            String::from(name)
+        },
+    }
+}
+
+/// The media type of the request body of an operation unless it is json,
+/// which is the only kind of body the clients can send.
+pub(super) fn unsupported_body<'a>(
+    spec: &'a OperationSpec,
+    components: Option<&'a ComponentsSpec>,
+) -> Option<&'a str> {
+    let body = spec.request_body.as_ref()?.resolve(components)?;
+    if body.content.keys().any(|it| specs::is_json_media_type(it)) {
+        return None;
+    }
+
+    body.content.keys().next().map(|it| it.as_str())
+}
+
+/// Finds the first successful response of an operation, falling back to the
+/// default response, and returns the media types it can be requested as,
+/// preferred one first. Event streams can't be offered next to the other
+/// media types since they are read as they arrive. Responses without a body
+/// yield `None`.
+pub(super) fn success_response(
+    responses: &BTreeMap<String, RefOr<ResponseSpec>>,
+    components: Option<&ComponentsSpec>,
+) -> Option<(Vec<String>, Option<RefOr<SchemaSpec>>)> {
+    let response = responses.iter()
+        .find(|(status, _)| status.starts_with('2'))
+        .map(|(_, it)| it)
+        .or_else(|| responses.get("default"))?;
+
+    let response = match (response.resolve(components), response) {
+        (Some(it), _) => it,
+        (None, RefOr::Ref { ref ref_path }) => {
+            println!("\tignoring response body, could not resolve {}", ref_path);
+            return None;
+        },
+        (None, _) => return None,
+    };
+
+    // prefer json if the response is offered in several formats
+    let (media_type, media_spec) = response.content.iter()
+        .find(|(media_type, _)| specs::media_type_essence(media_type) == "application/json")
+        .or_else(|| response.content.iter().find(|(it, _)| specs::is_json_media_type(it)))
+        .or_else(|| response.content.iter().next())?;
+
+    let preferred = specs::media_type_essence(media_type);
+    let response_type = ResponseType::from_media_type(&preferred);
+
+    let is_stream = response_type == ResponseType::EventStream;
+    let mut accept = vec![preferred.clone()];
+    for media_type in response.content.keys().map(|it| specs::media_type_essence(it)) {
+        if !accept.contains(&media_type)
+            && (ResponseType::from_media_type(&media_type) == ResponseType::EventStream) == is_stream {
+            accept.push(media_type);
+        }
+    }
+
+    Some((accept, media_spec.schema.clone()))
+}
+
+fn generate_models(
+    model_specs: &BTreeMap<String, RefOr<SchemaSpec>>,
+    options: &Options,
+) -> Vec<ModelFile> {
     let mut result = vec![];
 
     for (name, spec) in model_specs {
-        let (models, imports) = generate_model(&name, &spec, None);
+        let (models, imports) = generate_model(&name, &spec, None, options);
 
         let root = if !models.is_empty() {
             models[0].clone().1
@@ -306,14 +963,194 @@ fn generate_models(model_specs: &BTreeMap<String, RefOr<SchemaSpec>>) -> Vec<Mod
             .map(|(file, types)| GroupedImport { file, types })
             .collect();
 
+        let has_defaults = match root {
+            Model::Struct { ref fields, .. } => fields.iter().any(|it| it.default.is_some()),
+            _ => false,
+        };
+
         result.push(ModelFile {
+            with_defaults: options.with_defaults && has_defaults,
             imports, root, nested,
+            writable: None,
+            converters: needs_converters(options),
         });
     }
 
     result
 }
 
+/// Reference paths of the components that reference themselves.
+#[derive(Default)]
+struct Cycles {
+    /// Cycles that can't be expressed at all
+    aliases: HashSet<String>,
+    /// Cycles that can be expressed if maps are object literals
+    maps: HashSet<String>,
+}
+
+/// Finds the components that can't be emitted as type aliases because they
+/// reference themselves without an interface or array in between, e.g. a
+/// schema that is an alias of itself or a union containing itself. Maps are
+/// only taken into account if `include_maps` is set. Returns their reference
+/// paths.
+pub(super) fn alias_cycles(components: &ComponentsSpec, include_maps: bool) -> HashSet<String> {
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (name, spec) in components.schemas.iter() {
+        edges.insert(format!("#/components/schemas/{}", name), alias_refs(spec, include_maps));
+    }
+
+    for (name, spec) in components.responses.iter() {
+        let refs = match spec {
+            RefOr::Ref { ref ref_path } => vec![ref_path.clone()],
+            RefOr::Object(ref it) => specs::json_schema(&it.content)
+                .map_or_else(Vec::new, |it| alias_refs(&it, include_maps)),
+        };
+        edges.insert(format!("#/components/responses/{}", name), refs);
+    }
+
+    for (name, spec) in components.request_bodies.iter() {
+        let refs = match spec {
+            RefOr::Ref { ref ref_path } => vec![ref_path.clone()],
+            RefOr::Object(ref it) => specs::json_schema(&it.content)
+                .map_or_else(Vec::new, |it| alias_refs(&it, include_maps)),
+        };
+        edges.insert(format!("#/components/requestBodies/{}", name), refs);
+    }
+
+    util::cyclic_nodes(&edges)
+}
+
+/// The references a schema needs to be resolved eagerly, i.e. the ones that
+/// end up in a type alias instead of an interface or array.
+fn alias_refs(spec: &RefOr<SchemaSpec>, include_maps: bool) -> Vec<String> {
+    let spec = match spec {
+        RefOr::Ref { ref ref_path } => return vec![ref_path.clone()],
+        RefOr::Object(ref it) => it,
+    };
+
+    if !spec.schema_enum.is_empty() {
+        return vec![];
+    }
+
+    let variants: Vec<&RefOr<SchemaSpec>> = spec.all_of.iter()
+        .chain(spec.one_of.iter())
+        .chain(spec.any_of.iter())
+        .collect();
+    if !variants.is_empty() {
+        return variants.into_iter().flat_map(|it| alias_refs(it, include_maps)).collect();
+    }
+
+    match schema_type(spec) {
+        // maps are aliases of `Record`, which doesn't defer its arguments
+        "object" if include_maps && spec.properties.is_empty() => {
+            let mut refs = vec![];
+            if let Some(AdditionalPropertiesSpec::Schema(ref value)) = spec.additional_properties {
+                refs.extend(alias_refs(value, include_maps));
+            }
+            for value in spec.pattern_properties.values() {
+                refs.extend(alias_refs(value, include_maps));
+            }
+            refs
+        },
+        _ => vec![],
+    }
+}
+
+/// Replaces the roots that are part of a cycle by `unknown`, since TypeScript
+/// would reject them. Recursive string maps are written as object literals,
+/// which TypeScript resolves lazily unlike `Record`.
+fn break_cycles(files: &mut [ModelFile], section: &str, cycles: &Cycles) {
+    for file in files.iter_mut() {
+        let ref_path = format!("#/components/{}/{}", section, file.root.name());
+        if !cycles.maps.contains(&ref_path) {
+            continue;
+        }
+
+        if !cycles.aliases.contains(&ref_path) {
+            if let Model::Alias { ref mut alias, .. } = file.root {
+                let value_type = alias.strip_prefix("Record<string, ")
+                    .and_then(|it| it.strip_suffix('>'))
+                    .map(String::from);
+
+                if let Some(value_type) = value_type {
+                    *alias = format!("{{ [key: string]: {} }}", value_type);
+                    continue;
+                }
+            }
+        }
+
+        println!(
+            "{} references itself without an object or array in between, \
+            it is generated as unknown",
+            ref_path,
+        );
+
+        file.root = Model::Alias {
+            name: file.root.name(),
+            alias: String::from("unknown"),
+            is_array: false,
+            nullable: false,
+            conversion: None,
+            model: None,
+            rules: Box::default(),
+        };
+        file.imports.clear();
+        file.nested.clear();
+    }
+}
+
+/// Finds the component enums that are emitted as TypeScript enums but allow
+/// null, which the enum itself can't express.
+fn nullable_enums(schema_models: &[ModelFile]) -> HashSet<String> {
+    schema_models.iter()
+        .filter_map(|it| match it.root {
+            Model::Enum { ref name, as_union: false, nullable: true, .. } => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds the schemas that need a separate variant for request bodies, either
+/// because they have read or write only properties themselves or because they
+/// reference a schema that does.
+fn writable_variants(schema_models: &[ModelFile]) -> HashSet<String> {
+    let mut result = HashSet::new();
+
+    loop {
+        let found = result.len();
+
+        for file in schema_models {
+            let schema_imports = file.schema_imports();
+            let references_writable = |type_name: &str| {
+                let type_name = type_name.split(" | ").next().unwrap_or(type_name);
+                schema_imports.contains(type_name) && result.contains(type_name)
+            };
+
+            let needs_variant = match file.root {
+                Model::Struct { ref fields, .. } => fields.iter().any(|it| {
+                    it.read_only || it.write_only || references_writable(&it.field_type)
+                }),
+                Model::Alias { ref alias, .. } => references_writable(alias),
+                Model::Enum { .. } => false,
+            };
+
+            if needs_variant {
+                result.insert(file.root.name());
+            }
+        }
+
+        if result.len() == found {
+            return result;
+        }
+    }
+}
+
+/// Whether a type name was imported from the component schemas.
+fn is_schema_ref(type_name: &str, imports: &[Import]) -> bool {
+    imports.iter().any(|it| it.file == "schemas" && it.import_type == type_name)
+}
+
 fn get_ref(path: &String) -> (String, Import) {
     let parts = path
         .split("/")
@@ -329,13 +1166,59 @@ fn get_ref(path: &String) -> (String, Import) {
     (parts[3].clone(), import)
 }
 
+/// Whether any format is mapped to `Date`, in which case every model gets
+/// functions to convert it from and to its json representation.
+fn needs_converters(options: &Options) -> bool {
+    options.format_types.values().any(|it| it == "Date")
+}
+
+/// Maps a primitive schema type to a TypeScript type, taking the format into
+/// account. Formats can be mapped to other types on the command line.
+fn primitive_type(schema_type: &str, format: Option<&str>, options: &Options) -> String {
+    if let Some(mapped) = format.and_then(|it| options.format_types.get(it)) {
+        return mapped.clone();
+    }
+
+    let primitive = match (schema_type, format) {
+        // file contents, only useful for multipart and binary bodies
+        ("string", Some("binary")) => "Blob",
+        // date, date-time, uuid, byte (base64) and all custom formats
+        ("string", _) => "string",
+        // int32, int64, float and double alike, int64 loses precision
+        // beyond 2^53 but that's what JSON.parse does anyway
+        ("integer", _) | ("number", _) => "number",
+        ("boolean", _) => "boolean",
+        _ => "unknown",
+    };
+
+    String::from(primitive)
+}
+
+/// The conversion of a primitive, only dates need one.
+fn primitive_conversion(primitive: &str, format: Option<&str>) -> Option<Conversion> {
+    match (primitive, format) {
+        ("Date", Some("date")) => Some(Conversion::DateOnly),
+        ("Date", _) => Some(Conversion::Date),
+        _ => None,
+    }
+}
+
+/// Generated models along with the namespace they are nested in, the first
+/// one is the root.
+type NamespacedModels = Vec<(Option<String>, Model)>;
+
 fn generate_model(
     name: &str,
     spec: &RefOr<SchemaSpec>,
     namespace: Option<String>,
-) -> (Vec<(Option<String>, Model)>, Vec<Import>) {
+    options: &Options,
+) -> (NamespacedModels, Vec<Import>) {
     let mut models = Vec::new();
     let mut imports = Vec::new();
+    let converters = needs_converters(options);
+    let model_conversion = |name: String| {
+        if converters { Some(Conversion::Model { name }) } else { None }
+    };
 
     let child_namespace = namespace.clone().map_or(
         case::pascal_case(name),
@@ -348,8 +1231,12 @@ fn generate_model(
             imports.push(import);
             models.push((namespace, Model::Alias {
                 name: String::from(name),
+                conversion: model_conversion(ref_type.clone()),
+                model: Some(ref_type.clone()),
+                rules: Box::default(),
                 alias: ref_type,
                 is_array: false,
+                nullable: false,
             }));
 
             (models, imports)
@@ -358,25 +1245,11 @@ fn generate_model(
             // base case: enum at current level
             if !spec.schema_enum.is_empty() {
                 match spec.schema_type.as_ref().map(|it| it.as_str()) {
-                    Some("string") | None => {
-                        models.push((namespace, Model::Enum {
-                            name: String::from(name),
-                            variants: spec.schema_enum.iter().map(|it| {
-                                EnumVariant {
-                                    name: it.clone(),
-                                    value: it.clone(),
-                                }
-                            }).collect()
-                        }));
+                    Some("string") | Some("number") | Some("integer") | Some("boolean") | None => {
+                        models.push((namespace, generate_enum(name, spec, options)));
 
                         return (models, imports);
                     },
-                    Some("number") => {
-                        println!(
-                            "skipping {}, number enums are not supported",
-                            name
-                        );
-                    },
                     Some(other) => {
                         println!(
                             "skipping {}, invalid model type for enum: {}",
@@ -388,19 +1261,14 @@ fn generate_model(
                 return (models, imports);
             }
 
-            match spec.schema_type.as_ref().map(|it| it.as_str()) {
-                // base case
-                // TODO(hilmar): Respect format!
-                Some("string") | Some("number") => {
-                    models.push((namespace, Model::Alias {
-                        name: String::from(name),
-                        alias: spec.schema_type.clone().unwrap(),
-                        is_array: false,
-                    }));
+            if !spec.all_of.is_empty() || !spec.one_of.is_empty() || !spec.any_of.is_empty() {
+                return generate_composition(name, spec, namespace, options);
+            }
 
-                    (models, imports)
-                },
-                Some("array") => {
+            match schema_type(spec) {
+                "array" => {
+                    let nullable = spec.nullable;
+                    let array_spec = spec;
                     match spec.items {
                         Some(ref spec) => {
                             // this is needed due to the box
@@ -410,27 +1278,46 @@ fn generate_model(
                                     "Item",
                                     &RefOr::Object(*obj),
                                     Some(child_namespace.clone()),
+                                    options,
                                 ),
                                 RefOr::Ref { ref_path } => generate_model(
                                     "Item",
                                     &RefOr::Ref { ref_path },
                                     Some(child_namespace.clone()),
+                                    options,
                                 ),
                             };
 
-                            let (_, item_model) = item_models[0].clone();
+                            let item_model = match item_models.first() {
+                                Some((_, model)) => model.clone(),
+                                None => Model::Alias {
+                                    name: String::from("Item"),
+                                    alias: String::from("unknown"),
+                                    is_array: false,
+                                    nullable: false,
+                                    conversion: None,
+                                    model: None,
+                                    rules: Box::default(),
+                                },
+                            };
 
                             match item_model {
-                                // this means a base case happened
-                                Model::Alias { ref alias, is_array, .. } => {
-                                    if (is_array) {
-                                        panic!("can't have nested array fields at the moment");
-                                    }
-
+                                // this means a base case happened, nested
+                                // arrays end up in the item type
+                                Model::Alias { is_array, ref conversion, ref model, .. } => {
                                     models.push((namespace, Model::Alias {
                                         name: String::from(name),
-                                        alias: alias.clone(),
+                                        alias: type_name(&item_model, &child_namespace),
                                         is_array: true,
+                                        nullable,
+                                        model: model.clone(),
+                                        rules: Box::new(Rules::from_schema(array_spec, options)),
+                                        conversion: match conversion {
+                                            Some(it) if is_array => Some(Conversion::Array {
+                                                items: Box::new(it.clone()),
+                                            }),
+                                            other => other.clone(),
+                                        },
                                     }));
 
                                     models.extend(item_models.into_iter().skip(1));
@@ -438,14 +1325,20 @@ fn generate_model(
                                 },
                                 // this means we generated children
                                 _ => {
+                                    let alias = format!(
+                                        "{}.{}",
+                                        child_namespace.clone(),
+                                        item_model.name()
+                                    );
+
                                     models.push((namespace, Model::Alias {
                                         name: String::from(name),
-                                        alias: format!(
-                                            "{}.{}",
-                                            child_namespace.clone(),
-                                            item_model.name()
-                                        ),
+                                        conversion: model_conversion(alias.clone()),
+                                        model: Some(alias.clone()),
+                                        rules: Box::new(Rules::from_schema(array_spec, options)),
+                                        alias,
                                         is_array: true,
+                                        nullable,
                                     }));
 
                                     models.extend(item_models.into_iter());
@@ -463,14 +1356,15 @@ fn generate_model(
 
                     (models, imports)
                 },
-                Some("object") => {
+                "object" => {
                     let mut fields = vec![];
                     let mut sub_models = vec![];
 
                     for (field_name, field_spec) in spec.properties.iter() {
                         let (field_models, field_imports) = generate_model(
-                            &case::pascal_case(field_name), field_spec, 
+                            &case::pascal_case(field_name), field_spec,
                             Some(child_namespace.clone()),
+                            options,
                         );
 
                         // ignore namespace
@@ -479,13 +1373,17 @@ fn generate_model(
 
                         match field_model {
                             // this means a base case happened
-                            Model::Alias { ref alias, is_array, .. } => {
+                            Model::Alias { ref alias, is_array, ref conversion, ref model, ref rules, .. } => {
                                 fields.push(Field {
                                     name: field_name.clone(),
                                     field_type: alias.clone(),
                                     required: spec.required.iter()
                                         .any(|r| r == field_name),
                                     is_array,
+                                    conversion: conversion.clone(),
+                                    model: model.clone(),
+                                    rules: rules.as_ref().clone(),
+                                    ..Field::from_schema(field_spec)
                                 });
 
                                 sub_models.extend(field_models.into_iter().skip(1));
@@ -493,16 +1391,22 @@ fn generate_model(
                             },
                             // this means we generated children
                             _ => {
+                                let field_type = format!(
+                                    "{}.{}",
+                                    child_namespace.clone(),
+                                    field_model.name()
+                                );
+
                                 fields.push(Field {
                                     name: field_name.clone(),
-                                    field_type: format!(
-                                        "{}.{}",
-                                        child_namespace.clone(),
-                                        field_model.name()
-                                    ),
+                                    conversion: model_conversion(field_type.clone()),
+                                    model: Some(field_type.clone()),
+                                    rules: Rules::from_ref_or(field_spec, options),
+                                    field_type,
                                     required: spec.required.iter()
                                         .any(|r| r == field_name),
                                     is_array: false,
+                                    ..Field::from_schema(field_spec)
                                 });
 
                                 sub_models.extend(field_models.into_iter());
@@ -511,17 +1415,386 @@ fn generate_model(
                         }
                     }
 
+                    let (mut index_signatures, index_models, index_imports) =
+                        generate_index_signatures(spec, &child_namespace, options);
+                    sub_models.extend(index_models);
+                    imports.extend(index_imports);
+
+                    // pure maps don't need an interface of their own
+                    if fields.is_empty() && !index_signatures.is_empty() {
+                        models.push((namespace, Model::Alias {
+                            name: String::from(name),
+                            alias: map_type(spec, &index_signatures, &mut imports),
+                            is_array: false,
+                            nullable: spec.nullable,
+                            conversion: None,
+                            model: None,
+                            rules: Box::new(Rules::from_schema(spec, options)),
+                        }));
+                        models.extend(sub_models);
+
+                        return (models, imports);
+                    }
+
+                    // declared properties have to be assignable to the
+                    // string index signature
+                    for index in index_signatures.iter_mut().filter(|it| it.key_type == "string") {
+                        for field in fields.iter() {
+                            let mut field_type = if field.is_array {
+                                format!("Array<{}>", field.field_type)
+                            } else {
+                                field.field_type.clone()
+                            };
+
+                            if field.nullable { field_type.push_str(" | null"); }
+                            if !field.required { field_type.push_str(" | undefined"); }
+                            index.add_value_type(field_type);
+                        }
+                    }
+
                     models.push((namespace, Model::Struct {
                         name: String::from(name),
                         fields,
+                        index_signatures,
                     }));
                     models.extend(sub_models.into_iter());
 
                     (models, imports)
                 },
-                _ => (models, imports),
+                // base case
+                primitive => {
+                    let format = spec.format.as_ref().map(|it| it.as_str());
+                    let alias = primitive_type(primitive, format, options);
+
+                    models.push((namespace, Model::Alias {
+                        name: String::from(name),
+                        conversion: primitive_conversion(&alias, format),
+                        model: None,
+                        rules: Box::new(Rules::from_schema(spec, options)),
+                        alias,
+                        is_array: false,
+                        nullable: spec.nullable,
+                    }));
+
+                    (models, imports)
+                },
+            }
+        },
+    }
+}
+
+/// Generates an enum with a valid identifier for every value. `null` isn't a
+/// variant but makes the enum nullable. Enums with values TypeScript enums
+/// can't hold, like booleans, become unions.
+fn generate_enum(name: &str, spec: &SchemaSpec, options: &Options) -> Model {
+    let mut variants: Vec<EnumVariant> = vec![];
+
+    for (i, value) in spec.schema_enum.iter().enumerate() {
+        if value.is_null() {
+            continue;
+        }
+
+        let base_name = spec.enum_var_names.get(i).map_or_else(
+            || enum_variant_name(value),
+            |it| enum_variant_name(&serde_json::Value::String(it.clone())),
+        );
+
+        let mut variant_name = base_name.clone();
+        let mut suffix = 2;
+        while variants.iter().any(|it| it.name == variant_name) {
+            variant_name = format!("{}{}", base_name, suffix);
+            suffix += 1;
+        }
+
+        variants.push(EnumVariant {
+            name: variant_name,
+            value: value.to_string(),
+            description: spec.enum_descriptions.get(i).cloned().filter(|it| !it.is_empty()),
+        });
+    }
+
+    Model::Enum {
+        name: String::from(name),
+        as_union: options.enum_unions
+            || spec.schema_enum.iter().any(|it| !it.is_string() && !it.is_number() && !it.is_null()),
+        nullable: spec.nullable || spec.schema_enum.iter().any(|it| it.is_null()),
+        variants,
+    }
+}
+
+/// Turns an enum value into an identifier, e.g. `per-stay` into `per_stay`,
+/// `2fa` into `_2fa` and `-1` into `Minus1`.
+fn enum_variant_name(value: &serde_json::Value) -> String {
+    let value = match value {
+        serde_json::Value::String(it) => it.clone(),
+        serde_json::Value::Number(it) => {
+            let it = it.to_string();
+            match it.strip_prefix('-') {
+                Some(positive) => format!("Minus{}", positive),
+                None => format!("Value{}", it),
             }
         },
+        serde_json::Value::Bool(it) => case::pascal_case(&it.to_string()),
+        other => other.to_string(),
+    };
+
+    let mut name = String::new();
+    for c in value.chars() {
+        if c.is_alphanumeric() || c == '_' || c == '$' {
+            name.push(c);
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+
+    let name = name.trim_matches('_');
+    match name.chars().next() {
+        None => String::from("Empty"),
+        Some(first) if first.is_numeric() => format!("_{}", name),
+        Some(_) => String::from(name),
+    }
+}
+
+/// Generates a union of the `oneOf` or `anyOf` variants or an intersection of
+/// the `allOf` ones. Properties next to `allOf` form a variant of their own.
+fn generate_composition(
+    name: &str,
+    spec: &SchemaSpec,
+    namespace: Option<String>,
+    options: &Options,
+) -> (NamespacedModels, Vec<Import>) {
+    let mut sub_models = vec![];
+    let mut imports = vec![];
+    let child_namespace = namespace.clone().map_or(
+        case::pascal_case(name),
+        |parent| format!("{}.{}", parent, case::pascal_case(name)),
+    );
+
+    let mut own = spec.clone();
+    own.all_of.clear();
+    own.one_of.clear();
+    own.any_of.clear();
+    own.nullable = false;
+
+    let (mut variants, separator) = if !spec.all_of.is_empty() {
+        (spec.all_of.clone(), " & ")
+    } else if !spec.one_of.is_empty() {
+        (spec.one_of.clone(), " | ")
+    } else {
+        (spec.any_of.clone(), " | ")
+    };
+
+    if !own.properties.is_empty() {
+        own.schema_type = Some(String::from("object"));
+        variants.push(RefOr::Object(own));
+    }
+
+    let mut types = vec![];
+    let mut conversions = vec![];
+    for (i, variant) in variants.iter().enumerate() {
+        let (variant_models, variant_imports) = generate_model(
+            &format!("Variant{}", i + 1), variant,
+            Some(child_namespace.clone()), options,
+        );
+
+        let variant_type = match variant_models.first() {
+            Some((_, ref model)) => type_name(model, &child_namespace),
+            None => String::from("unknown"),
+        };
+
+        // unions can't be converted without knowing which variant it is,
+        // the parts of an intersection are converted one after the other
+        match variant_models.first() {
+            Some((_, Model::Alias { conversion: Some(Conversion::Model { ref name }), .. })) => {
+                conversions.push(name.clone());
+            },
+            Some((_, Model::Struct { .. })) if needs_converters(options) => {
+                conversions.push(variant_type.clone());
+            },
+            _ => {},
+        }
+
+        match variant_models.first() {
+            Some((_, Model::Alias { .. })) => sub_models.extend(variant_models.into_iter().skip(1)),
+            _ => sub_models.extend(variant_models),
+        }
+        imports.extend(variant_imports);
+
+        // unions bind weaker than intersections
+        if separator == " & " && variant_type.contains(" | ") {
+            types.push(format!("({})", variant_type));
+        } else {
+            types.push(variant_type);
+        }
+    }
+
+    let mut models = vec![(namespace, Model::Alias {
+        name: String::from(name),
+        alias: types.join(separator),
+        is_array: false,
+        nullable: spec.nullable,
+        conversion: if separator == " & " && !conversions.is_empty() {
+            Some(Conversion::All { models: conversions })
+        } else {
+            None
+        },
+        // variants aren't validated, only the composition as a whole
+        model: None,
+        rules: Box::new(Rules {
+            nullable: spec.nullable,
+            ..Rules::default()
+        }),
+    })];
+    models.extend(sub_models);
+
+    (models, imports)
+}
+
+/// Generates index signatures from `additionalProperties` and
+/// `patternProperties`. Patterns that are a plain prefix become template
+/// literal keys, all others are merged into the string signature since
+/// TypeScript can't express them. Unanchored patterns like `[0-9]+` match
+/// somewhere in a key, so they are treated as matching all keys.
+fn generate_index_signatures(
+    spec: &SchemaSpec,
+    namespace: &str,
+    options: &Options,
+) -> (Vec<IndexSignature>, NamespacedModels, Vec<Import>) {
+    let mut signatures: Vec<IndexSignature> = vec![];
+    let mut models = vec![];
+    let mut imports = vec![];
+
+    if let Some(AdditionalPropertiesSpec::Allowed(true)) = spec.additional_properties {
+        signatures.push(IndexSignature {
+            key_type: String::from("string"),
+            value_type: String::from("unknown"),
+        });
+    }
+
+    let mut add_signature = |key_type: String, value_name: &str, value_spec: &RefOr<SchemaSpec>| {
+        let (value_models, value_imports) = generate_model(
+            value_name, value_spec, Some(String::from(namespace)), options,
+        );
+
+        let value_type = match value_models.first() {
+            Some((_, ref model)) => type_name(model, namespace),
+            None => String::from("unknown"),
+        };
+
+        match value_models.first() {
+            Some((_, Model::Alias { .. })) => models.extend(value_models.into_iter().skip(1)),
+            _ => models.extend(value_models),
+        }
+        imports.extend(value_imports);
+
+        match signatures.iter_mut().find(|it| it.key_type == key_type) {
+            Some(signature) => signature.add_value_type(value_type),
+            None => signatures.push(IndexSignature { key_type, value_type }),
+        }
+    };
+
+    if let Some(AdditionalPropertiesSpec::Schema(ref value_spec)) = spec.additional_properties {
+        add_signature(String::from("string"), "Value", value_spec);
+    }
+
+    for (i, (pattern, value_spec)) in spec.pattern_properties.iter().enumerate() {
+        let prefix = pattern.trim_start_matches('^');
+        let is_prefix = pattern.starts_with('^') && !prefix.is_empty()
+            && prefix.chars().all(|it| it.is_alphanumeric() || it == '-' || it == '_');
+
+        let key_type = if is_prefix {
+            format!("`{}${{string}}`", prefix)
+        } else {
+            String::from("string")
+        };
+
+        add_signature(key_type, &format!("Pattern{}", i + 1), value_spec);
+    }
+
+    // prefixed keys are strings too, so their values have to be assignable
+    // to the string signature
+    let prefixed: Vec<String> = signatures.iter()
+        .filter(|it| it.key_type != "string")
+        .map(|it| it.value_type.clone())
+        .collect();
+    if let Some(index) = signatures.iter_mut().find(|it| it.key_type == "string") {
+        for value_type in prefixed {
+            index.add_value_type(value_type);
+        }
+    }
+
+    (signatures, models, imports)
+}
+
+/// Builds the type of an object that only consists of index signatures.
+/// `propertyNames` can restrict the keys if they are an enum.
+fn map_type(
+    spec: &SchemaSpec,
+    signatures: &[IndexSignature],
+    imports: &mut Vec<Import>,
+) -> String {
+    let key_type = match spec.property_names.as_ref().map(|it| it.as_ref()) {
+        Some(RefOr::Ref { ref ref_path }) => {
+            let (ref_type, import) = get_ref(ref_path);
+            imports.push(import);
+            Some(ref_type)
+        },
+        Some(RefOr::Object(ref names)) if !names.schema_enum.is_empty() => {
+            Some(names.schema_enum.iter()
+                .filter(|it| !it.is_null())
+                .map(|it| it.to_string())
+                .collect::<Vec<String>>()
+                .join(" | "))
+        },
+        _ => None,
+    };
+
+    match (key_type, signatures) {
+        (Some(key_type), _) => {
+            let value_type = signatures.iter()
+                .find(|it| it.key_type == "string")
+                .map_or("unknown", |it| it.value_type.as_str());
+
+            format!("Partial<Record<{}, {}>>", key_type, value_type)
+        },
+        (None, [signature]) if signature.key_type == "string" => {
+            format!("Record<string, {}>", signature.value_type)
+        },
+        (None, _) => {
+            let signatures = signatures.iter()
+                .map(|it| format!("[key: {}]: {}", it.key_type, it.value_type))
+                .collect::<Vec<String>>();
+
+            format!("{{ {} }}", signatures.join(", "))
+        },
+    }
+}
+
+/// The type expression a generated model is referenced by from within the
+/// given namespace.
+/// The union of the values of an enum, which can take the place of the enum
+/// itself. `None` for other models.
+fn literal_type(model: &Model) -> Option<String> {
+    match model {
+        Model::Enum { ref variants, nullable, .. } => {
+            let mut values: Vec<&str> = variants.iter().map(|it| it.value.as_str()).collect();
+            if *nullable {
+                values.push("null");
+            }
+            Some(values.join(" | "))
+        },
+        _ => None,
+    }
+}
+
+fn type_name(model: &Model, namespace: &str) -> String {
+    match model {
+        Model::Alias { ref alias, is_array, nullable, .. } => {
+            let alias = if *is_array { format!("Array<{}>", alias) } else { alias.clone() };
+            if *nullable { format!("{} | null", alias) } else { alias }
+        },
+        Model::Enum { nullable: true, .. } => format!("{}.{} | null", namespace, model.name()),
+        _ => format!("{}.{}", namespace, model.name()),
     }
 }
 
@@ -530,6 +1803,147 @@ struct ModelFile {
     imports: Vec<GroupedImport>,
     root: Model,
     nested: HashMap<String, Vec<Model>>,
+    /// Variant of the root that is sent in request bodies
+    writable: Option<Model>,
+    /// Whether to emit `withDefaults`, only structs with defaults get one
+    with_defaults: bool,
+    /// Whether to emit functions that convert the models from and to json
+    converters: bool,
+}
+
+impl ModelFile {
+    fn schema_imports(&self) -> HashSet<&str> {
+        self.imports.iter()
+            .filter(|it| it.file == "schemas")
+            .flat_map(|it| it.types.iter().map(|it| it.as_str()))
+            .collect()
+    }
+
+    /// Drops imports of the types this file declares itself, which
+    /// recursive models would otherwise import from their own index.
+    fn remove_local_imports(&mut self, file: &str) {
+        let mut local = vec![self.root.name()];
+        local.extend(self.writable.as_ref().map(|it| it.name()));
+
+        for group in self.imports.iter_mut().filter(|it| it.file == file) {
+            group.types.retain(|it| !local.contains(it));
+        }
+        self.imports.retain(|it| !it.types.is_empty());
+    }
+
+    /// Makes the fields and aliases referring to the given enums nullable,
+    /// the items of arrays in case of arrays.
+    fn add_enum_nulls(&mut self, enums: &HashSet<String>) {
+        let models = std::iter::once(&mut self.root)
+            .chain(self.writable.iter_mut())
+            .chain(self.nested.values_mut().flatten());
+
+        for model in models {
+            match model {
+                Model::Struct { ref mut fields, .. } => {
+                    for field in fields.iter_mut().filter(|it| enums.contains(&it.field_type)) {
+                        if field.is_array {
+                            field.field_type.push_str(" | null");
+                        } else {
+                            field.nullable = true;
+                            field.rules.nullable = true;
+                        }
+                    }
+                },
+                Model::Alias { ref mut alias, is_array, ref mut nullable, ref mut rules, .. } if enums.contains(alias) => {
+                    if *is_array {
+                        alias.push_str(" | null");
+                    } else {
+                        *nullable = true;
+                        rules.nullable = true;
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// The models of the file along with the names they are referenced by.
+    fn named_models(&self) -> Vec<(String, &Model)> {
+        let mut models = vec![(self.root.name(), &self.root)];
+        models.extend(self.writable.iter().map(|it| (it.name(), it)));
+        for (namespace, nested) in self.nested.iter() {
+            models.extend(nested.iter().map(|it| (format!("{}.{}", namespace, it.name()), it)));
+        }
+        models
+    }
+
+    fn add_imports(&mut self, imports: Vec<Import>) {
+        for import in imports {
+            match self.imports.iter_mut().find(|it| it.file == import.file) {
+                Some(group) => if !group.types.contains(&import.import_type) {
+                    group.types.push(import.import_type);
+                },
+                None => self.imports.push(GroupedImport {
+                    file: import.file,
+                    types: vec![import.import_type],
+                }),
+            }
+        }
+    }
+
+    /// Builds the variant of the root that is sent in request bodies. Read only
+    /// properties are dropped, write only ones are kept and referenced schemas
+    /// are replaced by their writable variants. Inline models in the namespace
+    /// are shared with the root.
+    fn writable_model(&self, name: &str, writable: &HashSet<String>) -> (Model, Vec<Import>) {
+        let schema_imports = self.schema_imports();
+        let mut imports = vec![];
+        let mut writable_type = |type_name: &str| {
+            let mut parts = type_name.splitn(2, " | ");
+            let base = parts.next().unwrap_or(type_name);
+            if !schema_imports.contains(base) || !writable.contains(base) {
+                return String::from(type_name);
+            }
+
+            let variant = format!("Writable{}", base);
+            imports.push(Import { import_type: variant.clone(), file: String::from("schemas") });
+            match parts.next() {
+                Some(rest) => format!("{} | {}", variant, rest),
+                None => variant,
+            }
+        };
+
+        let model = match self.root {
+            Model::Struct { ref fields, ref index_signatures, .. } => Model::Struct {
+                name: String::from(name),
+                index_signatures: index_signatures.clone(),
+                fields: fields.iter()
+                    .filter(|it| !it.read_only)
+                    .map(|it| Field {
+                        field_type: writable_type(&it.field_type),
+                        conversion: it.conversion.as_ref()
+                            .map(|it| it.writable(&mut writable_type)),
+                        model: it.model.as_ref().map(|it| writable_type(it)),
+                        write_only: false,
+                        ..it.clone()
+                    })
+                    .collect(),
+            },
+            Model::Alias { ref alias, is_array, nullable, ref conversion, ref model, ref rules, .. } => {
+                Model::Alias {
+                    name: String::from(name),
+                    alias: writable_type(alias),
+                    conversion: conversion.as_ref().map(|it| it.writable(&mut writable_type)),
+                    model: model.as_ref().map(|it| writable_type(it)),
+                    rules: rules.clone(),
+                    is_array, nullable,
+                }
+            },
+            Model::Enum { ref variants, as_union, nullable, .. } => Model::Enum {
+                name: String::from(name),
+                variants: variants.clone(),
+                as_union, nullable,
+            },
+        };
+
+        (model, imports)
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -539,17 +1953,189 @@ enum Model {
         name: String,
         alias: String,
         is_array: bool,
+        nullable: bool,
+        /// Conversion of the aliased type, or of the items for arrays
+        conversion: Option<Conversion>,
+        /// The model the alias refers to, or its items for arrays
+        model: Option<String>,
+        rules: Box<Rules>,
     },
     Enum {
         name: String,
         variants: Vec<EnumVariant>,
+        /// Emitted as a union of the values instead of a TypeScript enum
+        as_union: bool,
+        nullable: bool,
     },
     Struct {
         name: String,
         fields: Vec<Field>,
+        index_signatures: Vec<IndexSignature>,
     },
 }
 
+/// How a value is converted between json and its TypeScript type. Models
+/// are converted by their generated functions.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type")]
+enum Conversion {
+    Date,
+    /// A date without time, which is serialized as `YYYY-MM-DD`
+    DateOnly,
+    Model { name: String },
+    /// Arrays nested in arrays
+    Array { items: Box<Conversion> },
+    /// The models of an intersection, applied in order
+    All { models: Vec<String> },
+}
+
+impl Conversion {
+    /// The conversion without the models that don't convert anything, `None`
+    /// if nothing is left to convert.
+    fn prune(&self, converting: &HashSet<String>) -> Option<Conversion> {
+        match self {
+            Conversion::Model { ref name } if !converting.contains(name) => None,
+            Conversion::Array { ref items } => items.prune(converting)
+                .map(|it| Conversion::Array { items: Box::new(it) }),
+            Conversion::All { ref models } => {
+                let models: Vec<String> = models.iter()
+                    .filter(|it| converting.contains(*it))
+                    .cloned()
+                    .collect();
+                if models.is_empty() { None } else { Some(Conversion::All { models }) }
+            },
+            other => Some(other.clone()),
+        }
+    }
+
+    fn writable<F: FnMut(&str) -> String>(&self, writable_type: &mut F) -> Conversion {
+        match self {
+            Conversion::Model { ref name } => Conversion::Model { name: writable_type(name) },
+            Conversion::Array { ref items } => Conversion::Array {
+                items: Box::new(items.writable(writable_type)),
+            },
+            Conversion::All { ref models } => Conversion::All {
+                models: models.iter().map(|it| writable_type(it)).collect(),
+            },
+            other => other.clone(),
+        }
+    }
+}
+
+/// Constraints of a value that the generated validators check, serialized
+/// as the object literal they are passed as.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rules {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    value_type: Option<&'static str>,
+    #[serde(rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    values: Vec<serde_json::Value>,
+    #[serde(skip_serializing_if = "is_false")]
+    nullable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_length: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_length: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minimum: Option<f64>,
+    #[serde(skip_serializing_if = "is_false")]
+    exclusive_minimum: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    maximum: Option<f64>,
+    #[serde(skip_serializing_if = "is_false")]
+    exclusive_maximum: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    multiple_of: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_items: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_items: Option<u64>,
+    #[serde(skip_serializing_if = "is_false")]
+    unique_items: bool,
+    /// Rules of the items of arrays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    items: Option<Box<Rules>>,
+}
+
+fn is_false(value: &bool) -> bool { !*value }
+
+impl Rules {
+    /// References are checked by the validator of the referenced model.
+    fn from_ref_or(spec: &RefOr<SchemaSpec>, options: &Options) -> Rules {
+        match spec {
+            RefOr::Ref { .. } => Rules::default(),
+            RefOr::Object(ref spec) => Rules::from_schema(spec, options),
+        }
+    }
+
+    fn from_schema(spec: &SchemaSpec, options: &Options) -> Rules {
+        let format = spec.format.as_deref();
+        let value_type = match schema_type(spec) {
+            "string" => match primitive_type("string", format, options).as_str() {
+                "Date" => Some("date"),
+                "Blob" => Some("blob"),
+                "string" => Some("string"),
+                // mapped to a type that can't be checked
+                _ => None,
+            },
+            "integer" => Some("integer"),
+            "number" => Some("number"),
+            "boolean" => Some("boolean"),
+            "array" => Some("array"),
+            _ => None,
+        };
+
+        let lower_bound = spec.lower_bound();
+        let upper_bound = spec.upper_bound();
+
+        Rules {
+            value_type,
+            values: spec.schema_enum.iter().filter(|it| !it.is_null()).cloned().collect(),
+            nullable: spec.nullable || spec.schema_enum.iter().any(|it| it.is_null()),
+            min_length: spec.min_length,
+            max_length: spec.max_length,
+            pattern: spec.pattern.clone(),
+            minimum: lower_bound.map(|(it, _)| it),
+            exclusive_minimum: lower_bound.is_some_and(|(_, exclusive)| exclusive),
+            maximum: upper_bound.map(|(it, _)| it),
+            exclusive_maximum: upper_bound.is_some_and(|(_, exclusive)| exclusive),
+            multiple_of: spec.multiple_of,
+            min_items: spec.min_items,
+            max_items: spec.max_items,
+            unique_items: spec.unique_items,
+            items: match spec.items {
+                Some(RefOr::Object(ref items)) if value_type == Some("array") => {
+                    Some(Box::new(Rules::from_schema(items, options)))
+                },
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct IndexSignature {
+    pub key_type: String,
+    pub value_type: String,
+}
+
+impl IndexSignature {
+    /// Adds the members of a union that aren't part of the value type yet.
+    fn add_value_type(&mut self, value_type: String) {
+        for value_type in value_type.split(" | ") {
+            let exists = self.value_type.split(" | ").any(|it| it == value_type);
+            if value_type == "unknown" {
+                self.value_type = String::from(value_type);
+            } else if !exists && self.value_type != "unknown" {
+                self.value_type = format!("{} | {}", self.value_type, value_type);
+            }
+        }
+    }
+}
+
 impl Model {
     fn name(&self) -> String {
         match self {
@@ -558,6 +2144,17 @@ impl Model {
             Model::Struct { ref name, .. } => name.clone(),
         }
     }
+
+    /// Whether converting the model changes anything, given the names of the
+    /// models that do.
+    fn converts(&self, converting: &HashSet<String>) -> bool {
+        let converts = |it: &Option<Conversion>| it.as_ref().and_then(|it| it.prune(converting)).is_some();
+        match self {
+            Model::Struct { ref fields, .. } => fields.iter().any(|it| converts(&it.conversion)),
+            Model::Alias { ref conversion, .. } => converts(conversion),
+            Model::Enum { .. } => false,
+        }
+    }
 }
 
 impl TemplateContext for ModelFile {
@@ -571,14 +2168,17 @@ impl TemplateContext for ModelFile {
     }
 }
 
+/// Validators for the models of a model file.
 #[derive(Clone, Debug, Serialize)]
-struct IndexFile {
-    exports: Vec<String>,
+struct ValidationFile {
+    model: ModelFile,
 }
 
-impl TemplateContext for IndexFile {
-    fn template(&self) -> &'static str { "index.tera" }
-    fn filename(&self) -> String { String::from("index.ts") }
+impl TemplateContext for ValidationFile {
+    fn template(&self) -> &'static str { "validation.tera" }
+    fn filename(&self) -> String {
+        format!("{}.validation.ts", self.model.filename().trim_end_matches(".ts"))
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -596,15 +2196,54 @@ struct GroupedImport {
 #[derive(Clone, Debug, Serialize)]
 struct EnumVariant {
     pub name: String,
+    /// The value as json literal
     pub value: String,
+    pub description: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Default, Serialize)]
 struct Field {
     pub name: String,
+    /// The argument name of parameters
+    pub ident: String,
     pub field_type: String,
     pub required: bool,
     pub is_array: bool,
+    pub nullable: bool,
+    pub read_only: bool,
+    pub write_only: bool,
+    pub description: Option<String>,
+    /// Default and example values as json literals
+    pub default: Option<String>,
+    pub example: Option<String>,
+    /// Conversion of the value, or of the items for arrays
+    pub conversion: Option<Conversion>,
+    /// The model the value refers to, or its items for arrays
+    pub model: Option<String>,
+    pub rules: Rules,
+}
+
+impl Field {
+    fn prune_conversion(&mut self, converting: &HashSet<String>) {
+        self.conversion = self.conversion.as_ref().and_then(|it| it.prune(converting));
+    }
+
+    /// Takes the modifiers and documentation of a property from its schema,
+    /// references don't carry any.
+    fn from_schema(spec: &RefOr<SchemaSpec>) -> Field {
+        match spec {
+            RefOr::Ref { .. } => Field::default(),
+            RefOr::Object(ref spec) => Field {
+                nullable: spec.nullable || spec.schema_enum.iter().any(|it| it.is_null()),
+                read_only: spec.read_only,
+                write_only: spec.write_only,
+                description: Some(spec.description.clone()).filter(|it| !it.is_empty()),
+                default: spec.default.as_ref().map(|it| it.to_string()),
+                example: spec.example.as_ref().map(|it| it.to_string()),
+                ..Field::default()
+            },
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -613,7 +2252,6 @@ struct ServiceFile {
     client_name_kebab: String,
     imports: Vec<GroupedImport>,
     name: String,
-    base_path: String,
     endpoints: Vec<Endpoint>,
     models: Vec<Model>,
     nested: HashMap<String, Vec<Model>>,
@@ -624,30 +2262,258 @@ impl TemplateContext for ServiceFile {
     fn filename(&self) -> String { format!("{}.ts", case::kebab_case(&self.name)) }
 }
 
+/// The react query hooks of the operations of a service.
+#[derive(Serialize)]
+struct HooksFile<'a> {
+    service: &'a ServiceFile,
+    /// The tag in camel case, which prefixes the query keys
+    tag: String,
+    service_file: String,
+    hooks: Vec<Hook<'a>>,
+}
+
+#[derive(Serialize)]
+struct Hook<'a> {
+    endpoint: &'a Endpoint,
+    /// The operation id in pascal case
+    name: String,
+    /// Whether the operation is fetched with a query or sent with a mutation
+    query: bool,
+    /// Whether the operation takes any parameters, which are passed as one object
+    has_params: bool,
+    params_optional: bool,
+}
+
+impl<'a> TemplateContext for HooksFile<'a> {
+    fn template(&self) -> &'static str { "hooks.tera" }
+    fn filename(&self) -> String { format!("{}.ts", case::kebab_case(&self.tag)) }
+}
+
+#[derive(Serialize)]
+struct HooksContextFile {
+    client_name_pascal: String,
+    client_name_kebab: String,
+}
+
+impl TemplateContext for HooksContextFile {
+    fn template(&self) -> &'static str { "context.tera" }
+    fn filename(&self) -> String { String::from("context.ts") }
+}
+
 #[derive(Clone, Debug, Serialize)]
 struct Endpoint {
+    /// The operation id
     pub name: String,
+    /// The method name
+    pub ident: String,
     pub body_param: Option<Field>,
     pub path_params: Vec<Field>,
     pub query_params: Vec<Field>,
     pub header_params: Vec<Field>,
+    pub response_type: ResponseType,
     pub return_type: String,
+    /// How to convert the json response body, only the conversion and
+    /// `is_array` are set
+    pub response_conversion: Option<Field>,
+    pub accept: Vec<String>,
+    /// The media types in `accept` that are read differently than the
+    /// preferred one
+    pub alternatives: Vec<Alternative>,
+    pub content_types: Vec<String>,
+    pub security: Vec<Vec<String>>,
+    pub errors: Vec<ErrorResponse>,
+    pub error_type: String,
+    pub servers: Vec<Server>,
     pub method: String,
     pub path: String,
 }
 
+impl Endpoint {
+    /// Drops the conversions of models that don't convert anything.
+    fn prune_conversions(&mut self, converting: &HashSet<String>) {
+        let params = self.body_param.iter_mut()
+            .chain(self.path_params.iter_mut())
+            .chain(self.query_params.iter_mut())
+            .chain(self.header_params.iter_mut());
+        for param in params {
+            param.prune_conversion(converting);
+        }
+
+        if let Some(ref mut it) = self.response_conversion {
+            it.prune_conversion(converting);
+        }
+        self.response_conversion = self.response_conversion.take().filter(|it| it.conversion.is_some());
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ErrorResponse {
+    /// Either a status code literal or `number`
+    pub status: String,
+    pub body_type: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Alternative {
+    pub media_type: String,
+    pub response_type: ResponseType,
+}
+
+/// How the http client has to read a response body. Serializes to the
+/// values angular accepts for the `responseType` request option.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub(super) enum ResponseType {
+    #[serde(rename = "json")]
+    Json,
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "blob")]
+    Blob,
+    #[serde(rename = "arraybuffer")]
+    ArrayBuffer,
+    #[serde(rename = "event-stream")]
+    EventStream,
+}
+
+impl ResponseType {
+    /// The type a body is read as, json bodies are whatever their schema
+    /// says.
+    fn body_type(&self) -> &'static str {
+        match self {
+            ResponseType::Json => "unknown",
+            ResponseType::Blob => "Blob",
+            ResponseType::ArrayBuffer => "ArrayBuffer",
+            ResponseType::Text | ResponseType::EventStream => "string",
+        }
+    }
+
+    pub(super) fn from_media_type(media_type: &str) -> ResponseType {
+        match media_type {
+            it if specs::is_json_media_type(it) => ResponseType::Json,
+            "text/event-stream" => ResponseType::EventStream,
+            // raw bytes without any further meaning
+            "application/octet-stream" => ResponseType::ArrayBuffer,
+            "application/xml" => ResponseType::Text,
+            it if it.ends_with("+xml") => ResponseType::Text,
+            it if it.starts_with("text/") => ResponseType::Text,
+            // pdfs, images, archives and other downloads
+            _ => ResponseType::Blob,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type")]
+enum SecurityScheme {
+    ApiKey {
+        name: String,
+        description: Option<String>,
+        param_name: String,
+        location: String,
+    },
+    Basic {
+        name: String,
+        description: Option<String>,
+    },
+    Bearer {
+        name: String,
+        description: Option<String>,
+    },
+    Http {
+        name: String,
+        description: Option<String>,
+        scheme: String,
+    },
+}
+
 #[derive(Serialize)]
-struct ClientConfigFile(String);
+struct ClientConfigFile {
+    client_name_pascal: String,
+    client_name_kebab: String,
+    security_schemes: Vec<SecurityScheme>,
+}
+
 impl TemplateContext for ClientConfigFile {
     fn template(&self) -> &'static str { "config.tera" }
-    fn filename(&self) -> String { format!("{}.config.ts", case::kebab_case(&self.0)) }
+    fn filename(&self) -> String { format!("{}-client.config.ts", self.client_name_kebab) }
+}
+
+#[derive(Serialize)]
+struct ServiceImport {
+    name: String,
+    file: String,
 }
 
 #[derive(Serialize)]
-struct ClientModuleFile(String);
+struct ClientModuleFile {
+    client_name_pascal: String,
+    client_name_kebab: String,
+    services: Vec<ServiceImport>,
+    interceptor: bool,
+}
+
 impl TemplateContext for ClientModuleFile {
     fn template(&self) -> &'static str { "module.tera" }
-    fn filename(&self) -> String { format!("{}.module.ts", case::kebab_case(&self.0)) }
+    fn filename(&self) -> String { format!("{}-client.module.ts", self.client_name_kebab) }
+}
+
+#[derive(Serialize)]
+struct InterceptorFile {
+    client_name_pascal: String,
+    client_name_kebab: String,
+}
+
+impl TemplateContext for InterceptorFile {
+    fn template(&self) -> &'static str { "interceptor.tera" }
+    fn filename(&self) -> String { format!("{}-error.interceptor.ts", self.client_name_kebab) }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Server {
+    url: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    variables: BTreeMap<String, ServerVariable>,
+}
+
+impl<'a> From<&'a ServerSpec> for Server {
+    fn from(spec: &'a ServerSpec) -> Server {
+        Server {
+            url: spec.url.clone(),
+            description: spec.description.clone(),
+            variables: spec.variables.iter()
+                .map(|(name, it)| (name.clone(), ServerVariable {
+                    default: it.default.clone(),
+                    description: it.description.clone(),
+                    variable_enum: it.variable_enum.clone(),
+                }))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ServerVariable {
+    default: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    #[serde(rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    variable_enum: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ServersFile {
+    servers: Vec<Server>,
+    operation_servers: BTreeMap<String, Vec<Server>>,
+}
+
+impl TemplateContext for ServersFile {
+    fn template(&self) -> &'static str { "servers.tera" }
+    fn filename(&self) -> String { String::from("servers.ts") }
 }
 
 #[derive(Serialize)]
@@ -697,12 +2563,19 @@ impl TemplateContext for TypingsFile {
 }
 
 #[derive(Serialize)]
-struct UtilFile;
+struct UtilFile {}
 impl TemplateContext for UtilFile {
     fn template(&self) -> &'static str { "util.tera" }
     fn filename(&self) -> String { String::from("util.ts") }
 }
 
+#[derive(Serialize)]
+struct ValidationUtilFile {}
+impl TemplateContext for ValidationUtilFile {
+    fn template(&self) -> &'static str { "validation-util.tera" }
+    fn filename(&self) -> String { String::from("validation.ts") }
+}
+
 #[derive(Serialize)]
 struct VariablesFile;
 impl TemplateContext for VariablesFile {
@@ -0,0 +1,444 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use serde_derive::Serialize;
+use voca_rs::case;
+use crate::{
+    specs::{OpenApiSpec, SchemaSpec, RefOr, AdditionalPropertiesSpec},
+    gen::{Generator, Options, TemplateContext, IndexFile},
+    util,
+};
+use super::models::{SCHEMA_PREFIX, schema_type};
+use super::typescript::alias_cycles;
+
+/// Generates a zod schema for every component schema, which validates
+/// payloads at runtime and provides the TypeScript type via `z.infer`.
+pub struct ZodGenerator;
+impl Generator for ZodGenerator {
+    fn generate(spec: &OpenApiSpec, options: &Options) {
+        let templates = util::load_templates("zod").unwrap();
+
+        let schemas = match spec.components {
+            Some(ref it) if !it.schemas.is_empty() => &it.schemas,
+            _ => {
+                println!("no component schemas, nothing to generate");
+                return;
+            },
+        };
+
+        // recursive schemas need their type spelled out, since z.infer
+        // can't infer a type from itself
+        let edges: HashMap<String, Vec<String>> = schemas.iter()
+            .map(|(name, spec)| {
+                let refs = spec.references().into_iter()
+                    .filter_map(|it| it.strip_prefix(SCHEMA_PREFIX))
+                    .map(String::from)
+                    .collect();
+                (name.clone(), refs)
+            })
+            .collect();
+        let recursive = util::cyclic_nodes(&edges);
+        // ts_type writes maps as object literals, which defer their values
+        let aliases = spec.components.as_ref().map_or_else(HashSet::new, |it| alias_cycles(it, false));
+
+        let files: Vec<SchemaFile> = schemas.iter()
+            .map(|(name, spec)| {
+                let description = match spec {
+                    RefOr::Object(ref it) if !it.description.is_empty() => {
+                        Some(it.description.clone())
+                    },
+                    _ => None,
+                };
+
+                let ref_path = format!("{}{}", SCHEMA_PREFIX, name);
+                if aliases.contains(&ref_path) {
+                    println!(
+                        "{} references itself without an object or array in between, \
+                        it is generated as unknown",
+                        ref_path,
+                    );
+
+                    return SchemaFile {
+                        name: name.clone(),
+                        description,
+                        imports: vec![],
+                        schema: String::from("z.unknown()"),
+                        type_def: None,
+                    };
+                }
+
+                let mut context = SchemaContext {
+                    name, options,
+                    recursive: &recursive,
+                    imports: BTreeSet::new(),
+                };
+
+                let schema = zod_schema(spec, &mut context, 0);
+                let type_def = if recursive.contains(name) {
+                    Some(ts_type(spec, options, 0))
+                } else {
+                    None
+                };
+
+                SchemaFile {
+                    name: name.clone(),
+                    description,
+                    imports: context.imports.into_iter()
+                        .map(|it| SchemaImport { file: case::kebab_case(&it), name: it })
+                        .collect(),
+                    schema, type_def,
+                }
+            })
+            .collect();
+
+        let index = IndexFile {
+            exports: files.iter()
+                .map(|it| it.filename().trim_end_matches(".ts").to_owned())
+                .collect(),
+        };
+
+        util::write_templates(&templates, &files, Some("schemas")).unwrap();
+        util::write_templates(&templates, &vec![index], Some("schemas")).unwrap();
+    }
+}
+
+/// State while generating the schema of a single file.
+struct SchemaContext<'a> {
+    name: &'a str,
+    options: &'a Options,
+    recursive: &'a HashSet<String>,
+    /// Schemas referenced from other files
+    imports: BTreeSet<String>,
+}
+
+/// Builds the zod expression of a schema. Nested objects are indented by
+/// `depth` levels.
+fn zod_schema(spec: &RefOr<SchemaSpec>, context: &mut SchemaContext, depth: usize) -> String {
+    let spec = match spec {
+        RefOr::Ref { ref ref_path } => return zod_ref(ref_path, context),
+        RefOr::Object(ref it) => it,
+    };
+
+    let mut schema = if !spec.schema_enum.is_empty() {
+        zod_enum(spec)
+    } else if !spec.all_of.is_empty() {
+        let parts: Vec<String> = spec.all_of.iter()
+            .map(|it| zod_schema(it, context, depth))
+            .collect();
+        let mut schema = parts.join(".and(") + &")".repeat(parts.len() - 1);
+
+        // properties next to allOf are part of the intersection
+        if !spec.properties.is_empty() {
+            schema = format!("{}.and({})", schema, zod_object(spec, context, depth));
+        }
+        schema
+    } else if !spec.one_of.is_empty() || !spec.any_of.is_empty() {
+        let variants = if spec.one_of.is_empty() { &spec.any_of } else { &spec.one_of };
+        let variants: Vec<String> = variants.iter()
+            .map(|it| zod_schema(it, context, depth))
+            .collect();
+
+        if variants.len() == 1 {
+            variants[0].clone()
+        } else {
+            format!("z.union([{}])", variants.join(", "))
+        }
+    } else {
+        match schema_type(spec) {
+            "string" => zod_string(spec, context.options),
+            "integer" => format!("z.number().int(){}", number_constraints(spec)),
+            "number" => format!("z.number(){}", number_constraints(spec)),
+            "boolean" => String::from("z.boolean()"),
+            "array" => {
+                let items = match spec.items {
+                    Some(RefOr::Ref { ref ref_path }) => zod_ref(ref_path, context),
+                    Some(RefOr::Object(ref it)) => {
+                        zod_schema(&RefOr::Object((**it).clone()), context, depth)
+                    },
+                    None => String::from("z.unknown()"),
+                };
+
+                let mut schema = format!("z.array({})", items);
+                if let Some(min) = spec.min_items { schema.push_str(&format!(".min({})", min)); }
+                if let Some(max) = spec.max_items { schema.push_str(&format!(".max({})", max)); }
+                if spec.unique_items {
+                    schema.push_str(
+                        ".refine(items => new Set(items.map(it => JSON.stringify(it))).size === items.length, \
+                        'must not contain duplicates')"
+                    );
+                }
+                schema
+            },
+            "object" => zod_object(spec, context, depth),
+            _ => String::from("z.unknown()"),
+        }
+    };
+
+    let nullable = spec.nullable || spec.schema_enum.iter().any(|it| it.is_null());
+    if nullable { schema.push_str(".nullable()"); }
+    if let Some(ref default) = spec.default {
+        schema.push_str(&format!(".default({})", default));
+    }
+    if !spec.description.is_empty() {
+        schema.push_str(&format!(".describe({})", serde_json::Value::from(spec.description.clone())));
+    }
+
+    schema
+}
+
+/// References schemas of other files directly, recursive ones lazily since
+/// they may not be initialized yet.
+fn zod_ref(ref_path: &str, context: &mut SchemaContext) -> String {
+    let name = match ref_path.strip_prefix(SCHEMA_PREFIX) {
+        Some(it) => it,
+        None => {
+            println!("skipping reference {}, only component schemas are supported", ref_path);
+            return String::from("z.unknown()");
+        },
+    };
+
+    if name != context.name {
+        context.imports.insert(String::from(name));
+    }
+
+    if context.recursive.contains(name) {
+        format!("z.lazy(() => {})", name)
+    } else {
+        String::from(name)
+    }
+}
+
+fn zod_enum(spec: &SchemaSpec) -> String {
+    let values: Vec<&serde_json::Value> = spec.schema_enum.iter()
+        .filter(|it| !it.is_null())
+        .collect();
+
+    match values.as_slice() {
+        [] => String::from("z.null()"),
+        [value] => format!("z.literal({})", value),
+        _ if values.iter().all(|it| it.is_string()) => {
+            let values: Vec<String> = values.iter().map(|it| it.to_string()).collect();
+            format!("z.enum([{}])", values.join(", "))
+        },
+        _ => {
+            let literals: Vec<String> = values.iter()
+                .map(|it| format!("z.literal({})", it))
+                .collect();
+            format!("z.union([{}])", literals.join(", "))
+        },
+    }
+}
+
+fn zod_string(spec: &SchemaSpec, options: &Options) -> String {
+    let format = spec.format.as_deref();
+    match format.and_then(|it| options.format_types.get(it)).map(|it| it.as_str()) {
+        Some("Date") => return String::from("z.coerce.date()"),
+        Some("string") | None => {},
+        Some(other) => println!("ignoring the mapping of {:?} to {}, zod schemas only support Date", format, other),
+    }
+
+    let mut schema = match format {
+        Some("binary") => return String::from("z.instanceof(Blob)"),
+        Some("uuid") => String::from("z.string().uuid()"),
+        Some("email") => String::from("z.string().email()"),
+        Some("uri") | Some("url") => String::from("z.string().url()"),
+        Some("date-time") => String::from("z.string().datetime({ offset: true })"),
+        _ => String::from("z.string()"),
+    };
+
+    if let Some(min) = spec.min_length { schema.push_str(&format!(".min({})", min)); }
+    if let Some(max) = spec.max_length { schema.push_str(&format!(".max({})", max)); }
+    if let Some(ref pattern) = spec.pattern {
+        schema.push_str(&format!(".regex(new RegExp({}))", serde_json::Value::from(pattern.clone())));
+    }
+
+    schema
+}
+
+fn number_constraints(spec: &SchemaSpec) -> String {
+    let mut constraints = String::new();
+    match spec.lower_bound() {
+        Some((bound, true)) => constraints.push_str(&format!(".gt({})", bound)),
+        Some((bound, false)) => constraints.push_str(&format!(".gte({})", bound)),
+        None => {},
+    }
+    match spec.upper_bound() {
+        Some((bound, true)) => constraints.push_str(&format!(".lt({})", bound)),
+        Some((bound, false)) => constraints.push_str(&format!(".lte({})", bound)),
+        None => {},
+    }
+    if let Some(multiple) = spec.multiple_of {
+        constraints.push_str(&format!(".multipleOf({})", multiple));
+    }
+
+    constraints
+}
+
+fn zod_object(spec: &SchemaSpec, context: &mut SchemaContext, depth: usize) -> String {
+    let additional = match spec.additional_properties {
+        Some(AdditionalPropertiesSpec::Schema(ref it)) => Some(zod_schema(it, context, depth)),
+        Some(AdditionalPropertiesSpec::Allowed(true)) => Some(String::from("z.unknown()")),
+        _ => None,
+    };
+
+    // pattern properties can't be told apart from additional ones
+    let mut extra: Vec<String> = additional.into_iter().collect();
+    extra.extend(spec.pattern_properties.values().map(|it| zod_schema(it, context, depth)));
+    let extra = match extra.len() {
+        0 => None,
+        1 => Some(extra.remove(0)),
+        _ => Some(format!("z.union([{}])", extra.join(", "))),
+    };
+
+    if spec.properties.is_empty() {
+        return match extra {
+            Some(value) => format!("z.record(z.string(), {})", value),
+            None => String::from("z.object({})"),
+        };
+    }
+
+    let indent = "    ".repeat(depth + 1);
+    let mut schema = String::from("z.object({\n");
+    for (name, property) in spec.properties.iter() {
+        let mut property_schema = zod_schema(property, context, depth + 1);
+
+        let has_default = match property {
+            RefOr::Object(ref it) => it.default.is_some(),
+            _ => false,
+        };
+        if !spec.required.contains(name) && !has_default {
+            property_schema.push_str(".optional()");
+        }
+
+        schema.push_str(&format!("{}{}: {},\n", indent, serde_json::Value::from(name.clone()), property_schema));
+    }
+    schema.push_str(&format!("{}}})", "    ".repeat(depth)));
+
+    match extra {
+        Some(value) => format!("{}.catchall({})", schema, value),
+        None => schema,
+    }
+}
+
+/// Builds the TypeScript type a schema parses into, which recursive schemas
+/// have to declare explicitly. Properties with defaults are optional since
+/// they are for the input of the schema.
+fn ts_type(spec: &RefOr<SchemaSpec>, options: &Options, depth: usize) -> String {
+    let spec = match spec {
+        RefOr::Ref { ref ref_path } => {
+            return String::from(ref_path.strip_prefix(SCHEMA_PREFIX).unwrap_or("unknown"));
+        },
+        RefOr::Object(ref it) => it,
+    };
+
+    let ts_type = if !spec.schema_enum.is_empty() {
+        let values: Vec<String> = spec.schema_enum.iter()
+            .filter(|it| !it.is_null())
+            .map(|it| it.to_string())
+            .collect();
+        if values.is_empty() { String::from("null") } else { values.join(" | ") }
+    } else if !spec.all_of.is_empty() {
+        let mut parts: Vec<String> = spec.all_of.iter()
+            .map(|it| format!("({})", ts_type(it, options, depth)))
+            .collect();
+        if !spec.properties.is_empty() {
+            parts.push(ts_object(spec, options, depth));
+        }
+        parts.join(" & ")
+    } else if !spec.one_of.is_empty() || !spec.any_of.is_empty() {
+        let variants = if spec.one_of.is_empty() { &spec.any_of } else { &spec.one_of };
+        variants.iter()
+            .map(|it| format!("({})", ts_type(it, options, depth)))
+            .collect::<Vec<String>>()
+            .join(" | ")
+    } else {
+        match schema_type(spec) {
+            "string" => {
+                let format = spec.format.as_deref();
+                match (format, format.and_then(|it| options.format_types.get(it))) {
+                    (_, Some(mapped)) if mapped == "Date" => String::from("Date"),
+                    (Some("binary"), _) => String::from("Blob"),
+                    _ => String::from("string"),
+                }
+            },
+            "integer" | "number" => String::from("number"),
+            "boolean" => String::from("boolean"),
+            "array" => {
+                let items = match spec.items {
+                    Some(RefOr::Ref { ref ref_path }) => {
+                        ts_type(&RefOr::Ref { ref_path: ref_path.clone() }, options, depth)
+                    },
+                    Some(RefOr::Object(ref it)) => {
+                        ts_type(&RefOr::Object((**it).clone()), options, depth)
+                    },
+                    None => String::from("unknown"),
+                };
+                format!("Array<{}>", items)
+            },
+            "object" => ts_object(spec, options, depth),
+            _ => String::from("unknown"),
+        }
+    };
+
+    if spec.nullable || spec.schema_enum.iter().any(|it| it.is_null()) {
+        format!("{} | null", ts_type)
+    } else {
+        ts_type
+    }
+}
+
+fn ts_object(spec: &SchemaSpec, options: &Options, depth: usize) -> String {
+    let mut extra: Vec<String> = vec![];
+    match spec.additional_properties {
+        Some(AdditionalPropertiesSpec::Schema(ref it)) => extra.push(ts_type(it, options, depth)),
+        Some(AdditionalPropertiesSpec::Allowed(true)) => extra.push(String::from("unknown")),
+        _ => {},
+    }
+    extra.extend(spec.pattern_properties.values().map(|it| ts_type(it, options, depth)));
+
+    let indent = "    ".repeat(depth + 1);
+    let mut object = String::from("{\n");
+    for (name, property) in spec.properties.iter() {
+        let has_default = match property {
+            RefOr::Object(ref it) => it.default.is_some(),
+            _ => false,
+        };
+        let optional = !spec.required.contains(name) || has_default;
+
+        object.push_str(&format!(
+            "{}{}{}: {},\n",
+            indent, serde_json::Value::from(name.clone()),
+            if optional { "?" } else { "" },
+            ts_type(property, options, depth + 1),
+        ));
+    }
+
+    if !extra.is_empty() {
+        // the declared properties have to fit the index signature
+        if !spec.properties.is_empty() { extra.push(String::from("unknown")); }
+        object.push_str(&format!("{}[key: string]: {},\n", indent, extra.join(" | ")));
+    }
+    object.push_str(&format!("{}}}", "    ".repeat(depth)));
+
+    object
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SchemaImport {
+    name: String,
+    file: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SchemaFile {
+    name: String,
+    description: Option<String>,
+    imports: Vec<SchemaImport>,
+    /// The zod expression
+    schema: String,
+    /// Explicit type of recursive schemas
+    type_def: Option<String>,
+}
+
+impl TemplateContext for SchemaFile {
+    fn template(&self) -> &'static str { "schema.tera" }
+    fn filename(&self) -> String { format!("{}.ts", case::kebab_case(&self.name)) }
+}
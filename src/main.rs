@@ -12,7 +12,7 @@ use std::{
 };
 
 use specs::OpenApiSpec;
-use gen::{Generator, Typescript};
+use gen::{Generator, Options, Typescript, Fetch, ReactQuery, Axios, Zod, RustClient, RustServer, Python, Go, Kotlin, Swift, JsonSchema, Markdown, Html, Postman};
 use tera;
 
 #[derive(Debug)]
@@ -34,12 +34,17 @@ impl From<std::io::Error> for Error {
 }
 
 fn main() -> Result<(), Error> {
-    let args = env::args().collect::<Vec<String>>();
-    if args.len() < 2 {
-        panic!("please supply a file name");
-    }
+    let (flags, args): (Vec<String>, Vec<String>) = env::args()
+        .partition(|it| it.starts_with("--"));
+
+    // the target is optional and defaults to the angular client
+    let (target, file) = match args.len() {
+        2 => ("angular", &args[1]),
+        3 => (args[1].as_str(), &args[2]),
+        _ => panic!("usage: swaglord-rs [target] <file>"),
+    };
 
-    let path = Path::new(&args[1]);
+    let path = Path::new(file);
     if !path.is_file() {
         panic!("path does not point to a file");
     }
@@ -50,7 +55,25 @@ fn main() -> Result<(), Error> {
     f.read_to_string(&mut contents).expect("error reading file");
 
     let spec = serde_yaml::from_str::<OpenApiSpec>(&contents).unwrap();
-    Typescript::generate(&spec);
+    let options = Options::from_args(&flags);
+    match target {
+        "angular" => Typescript::generate(&spec, &options),
+        "fetch" => Fetch::generate(&spec, &options),
+        "react-query" => ReactQuery::generate(&spec, &options),
+        "axios" => Axios::generate(&spec, &options),
+        "zod" => Zod::generate(&spec, &options),
+        "rust-client" => RustClient::generate(&spec, &options),
+        "rust-server" => RustServer::generate(&spec, &options),
+        "python" => Python::generate(&spec, &options),
+        "go" => Go::generate(&spec, &options),
+        "kotlin" => Kotlin::generate(&spec, &options),
+        "swift" => Swift::generate(&spec, &options),
+        "export-json-schema" => JsonSchema::generate(&spec, &options),
+        "markdown" => Markdown::generate(&spec, &options),
+        "html" => Html::generate(&spec, &options),
+        "postman" => Postman::generate(&spec, &options),
+        other => panic!("unknown target {}, expected one of angular, fetch, react-query, axios, zod, rust-client, rust-server, python, go, kotlin, swift, export-json-schema, markdown, html, postman", other),
+    }
 
     Ok(())
 }
@@ -17,6 +17,9 @@ pub struct OpenApiSpec {
 
     #[serde(default)]
     pub servers: Vec<ServerSpec>,
+
+    #[serde(default)]
+    pub security: Vec<SecurityRequirementSpec>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -27,6 +30,32 @@ pub struct OpenApiInfoSpec {
 #[derive(Debug, Deserialize, Clone)]
 pub struct ServerSpec {
     pub url: String,
+
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub variables: BTreeMap<String, ServerVariableSpec>,
+}
+
+impl ServerSpec {
+    /// The server that is assumed if a spec doesn't declare any.
+    pub fn root() -> ServerSpec {
+        ServerSpec {
+            url: String::from("/"),
+            description: None,
+            variables: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ServerVariableSpec {
+    pub default: String,
+
+    pub description: Option<String>,
+
+    #[serde(default, rename = "enum")]
+    pub variable_enum: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -57,6 +86,78 @@ impl<T> RefOr<T> where T: Clone {
     }
 }
 
+impl<T> RefOr<T> where T: Clone + Component {
+    /// Follows references into the components section until an object is
+    /// found. Returns `None` for dangling, external or circular references.
+    pub fn resolve<'a>(&'a self, components: Option<&'a ComponentsSpec>) -> Option<&'a T> {
+        let mut current = self;
+        let mut visited: Vec<&str> = vec![];
+
+        loop {
+            match current {
+                RefOr::Object(ref it) => return Some(it),
+                RefOr::Ref { ref ref_path } => {
+                    if visited.contains(&ref_path.as_str()) {
+                        return None;
+                    }
+
+                    visited.push(ref_path);
+                    let name = ref_path.strip_prefix(T::REF_PREFIX)?;
+                    current = T::lookup(components?, name)?;
+                },
+            }
+        }
+    }
+}
+
+/// Objects that can be referenced from the components section.
+pub trait Component: Sized + Clone {
+    const REF_PREFIX: &'static str;
+    fn lookup<'a>(components: &'a ComponentsSpec, name: &str) -> Option<&'a RefOr<Self>>;
+}
+
+impl Component for SchemaSpec {
+    const REF_PREFIX: &'static str = "#/components/schemas/";
+    fn lookup<'a>(components: &'a ComponentsSpec, name: &str) -> Option<&'a RefOr<Self>> {
+        components.schemas.get(name)
+    }
+}
+
+impl Component for ResponseSpec {
+    const REF_PREFIX: &'static str = "#/components/responses/";
+    fn lookup<'a>(components: &'a ComponentsSpec, name: &str) -> Option<&'a RefOr<Self>> {
+        components.responses.get(name)
+    }
+}
+
+impl Component for ParameterSpec {
+    const REF_PREFIX: &'static str = "#/components/parameters/";
+    fn lookup<'a>(components: &'a ComponentsSpec, name: &str) -> Option<&'a RefOr<Self>> {
+        components.parameters.get(name)
+    }
+}
+
+impl Component for RequestBodySpec {
+    const REF_PREFIX: &'static str = "#/components/requestBodies/";
+    fn lookup<'a>(components: &'a ComponentsSpec, name: &str) -> Option<&'a RefOr<Self>> {
+        components.request_bodies.get(name)
+    }
+}
+
+impl Component for SecuritySchemeSpec {
+    const REF_PREFIX: &'static str = "#/components/securitySchemes/";
+    fn lookup<'a>(components: &'a ComponentsSpec, name: &str) -> Option<&'a RefOr<Self>> {
+        components.security_schemes.get(name)
+    }
+}
+
+impl Component for HeaderSpec {
+    const REF_PREFIX: &'static str = "#/components/headers/";
+    fn lookup<'a>(components: &'a ComponentsSpec, name: &str) -> Option<&'a RefOr<Self>> {
+        components.headers.get(name)
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct ComponentsSpec {
     #[serde(default)]
@@ -74,6 +175,10 @@ pub struct ComponentsSpec {
 
     #[serde(default)]
     pub headers: BTreeMap<String, RefOr<HeaderSpec>>,
+
+    #[serde(rename = "securitySchemes")]
+    #[serde(default)]
+    pub security_schemes: BTreeMap<String, RefOr<SecuritySchemeSpec>>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -87,8 +192,15 @@ pub struct SchemaSpec {
     #[serde(default)]
     pub required: Vec<String>,
 
+    /// Any json scalars, `null` makes the schema nullable
     #[serde(default,rename = "enum")]
-    pub schema_enum: Vec<String>,
+    pub schema_enum: Vec<serde_json::Value>,
+
+    #[serde(default, rename = "x-enum-varnames")]
+    pub enum_var_names: Vec<String>,
+
+    #[serde(default, rename = "x-enum-descriptions")]
+    pub enum_descriptions: Vec<String>,
 
     pub format: Option<String>,
 
@@ -96,6 +208,158 @@ pub struct SchemaSpec {
 
     #[serde(default)]
     pub properties: BTreeMap<String, RefOr<SchemaSpec>>,
+
+    #[serde(default)]
+    pub nullable: bool,
+
+    #[serde(default, rename = "readOnly")]
+    pub read_only: bool,
+
+    #[serde(default, rename = "writeOnly")]
+    pub write_only: bool,
+
+    pub default: Option<serde_json::Value>,
+
+    pub example: Option<serde_json::Value>,
+
+    #[serde(rename = "additionalProperties")]
+    pub additional_properties: Option<AdditionalPropertiesSpec>,
+
+    #[serde(default, rename = "patternProperties")]
+    pub pattern_properties: BTreeMap<String, RefOr<SchemaSpec>>,
+
+    #[serde(rename = "propertyNames")]
+    pub property_names: Option<Box<RefOr<SchemaSpec>>>,
+
+    #[serde(default, rename = "oneOf")]
+    pub one_of: Vec<RefOr<SchemaSpec>>,
+
+    #[serde(default, rename = "anyOf")]
+    pub any_of: Vec<RefOr<SchemaSpec>>,
+
+    #[serde(default, rename = "allOf")]
+    pub all_of: Vec<RefOr<SchemaSpec>>,
+
+    pub discriminator: Option<DiscriminatorSpec>,
+
+    #[serde(rename = "minLength")]
+    pub min_length: Option<u64>,
+
+    #[serde(rename = "maxLength")]
+    pub max_length: Option<u64>,
+
+    pub pattern: Option<String>,
+
+    pub minimum: Option<f64>,
+
+    pub maximum: Option<f64>,
+
+    #[serde(rename = "exclusiveMinimum")]
+    pub exclusive_minimum: Option<ExclusiveBoundSpec>,
+
+    #[serde(rename = "exclusiveMaximum")]
+    pub exclusive_maximum: Option<ExclusiveBoundSpec>,
+
+    #[serde(rename = "multipleOf")]
+    pub multiple_of: Option<f64>,
+
+    #[serde(rename = "minItems")]
+    pub min_items: Option<u64>,
+
+    #[serde(rename = "maxItems")]
+    pub max_items: Option<u64>,
+
+    #[serde(default, rename = "uniqueItems")]
+    pub unique_items: bool,
+}
+
+/// Names the property that tells the variants of a composition apart.
+#[derive(Debug, Deserialize, Clone)]
+pub struct DiscriminatorSpec {
+    #[serde(rename = "propertyName")]
+    pub property_name: String,
+
+    /// Maps property values to schema references, values of variants that
+    /// aren't listed are their schema names.
+    #[serde(default)]
+    pub mapping: BTreeMap<String, String>,
+}
+
+/// OpenAPI 3.0 marks `minimum` and `maximum` as exclusive, 3.1 uses the
+/// JSON Schema form that carries the bound itself.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ExclusiveBoundSpec {
+    Flag(bool),
+    Bound(f64),
+}
+
+impl RefOr<SchemaSpec> {
+    /// All references in the schema, including the ones of nested schemas.
+    pub fn references(&self) -> Vec<&str> {
+        let mut refs = vec![];
+        match self {
+            RefOr::Ref { ref ref_path } => refs.push(ref_path.as_str()),
+            RefOr::Object(ref it) => it.collect_references(&mut refs),
+        }
+        refs
+    }
+}
+
+impl SchemaSpec {
+    fn collect_references<'a>(&'a self, refs: &mut Vec<&'a str>) {
+        let mut children: Vec<&RefOr<SchemaSpec>> = self.properties.values()
+            .chain(self.pattern_properties.values())
+            .chain(self.all_of.iter())
+            .chain(self.one_of.iter())
+            .chain(self.any_of.iter())
+            .collect();
+
+        if let Some(AdditionalPropertiesSpec::Schema(ref it)) = self.additional_properties {
+            children.push(it);
+        }
+        if let Some(ref it) = self.property_names {
+            children.push(it);
+        }
+
+        for child in children {
+            match child {
+                RefOr::Ref { ref ref_path } => refs.push(ref_path),
+                RefOr::Object(ref it) => it.collect_references(refs),
+            }
+        }
+
+        match self.items {
+            Some(RefOr::Ref { ref ref_path }) => refs.push(ref_path),
+            Some(RefOr::Object(ref it)) => it.collect_references(refs),
+            None => {},
+        }
+    }
+
+    /// The lower bound and whether it is exclusive.
+    pub fn lower_bound(&self) -> Option<(f64, bool)> {
+        match self.exclusive_minimum {
+            Some(ExclusiveBoundSpec::Bound(bound)) => Some((bound, true)),
+            Some(ExclusiveBoundSpec::Flag(exclusive)) => self.minimum.map(|it| (it, exclusive)),
+            None => self.minimum.map(|it| (it, false)),
+        }
+    }
+
+    /// The upper bound and whether it is exclusive.
+    pub fn upper_bound(&self) -> Option<(f64, bool)> {
+        match self.exclusive_maximum {
+            Some(ExclusiveBoundSpec::Bound(bound)) => Some((bound, true)),
+            Some(ExclusiveBoundSpec::Flag(exclusive)) => self.maximum.map(|it| (it, exclusive)),
+            None => self.maximum.map(|it| (it, false)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum AdditionalPropertiesSpec {
+    Allowed(bool),
+    Schema(Box<RefOr<SchemaSpec>>),
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -111,7 +375,36 @@ pub struct ResponseSpec {
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct MediaTypeSpec {
-    pub schema: RefOr<SchemaSpec>,
+    pub schema: Option<RefOr<SchemaSpec>>,
+}
+
+/// Strips parameters like `charset` from a media type.
+pub fn media_type_essence(media_type: &str) -> String {
+    media_type.split(';').next().unwrap_or("").trim().to_lowercase()
+}
+
+/// Whether a media type carries json, including structured syntax suffixes
+/// like `application/vnd.api+json`.
+pub fn is_json_media_type(media_type: &str) -> bool {
+    let essence = media_type_essence(media_type);
+    essence == "application/json" || essence.ends_with("+json")
+}
+
+/// The names of the placeholders of a path, e.g. `id` of `/pets/{id}`.
+pub fn path_placeholders(path: &str) -> Vec<&str> {
+    path.split('{')
+        .skip(1)
+        .filter_map(|it| it.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
+/// Finds the schema of the json body in a content map, preferring plain
+/// `application/json` over suffixed media types.
+pub fn json_schema(content: &BTreeMap<String, MediaTypeSpec>) -> Option<RefOr<SchemaSpec>> {
+    content.iter()
+        .find(|(media_type, _)| media_type_essence(media_type) == "application/json")
+        .or_else(|| content.iter().find(|(media_type, _)| is_json_media_type(media_type)))
+        .and_then(|(_, it)| it.schema.clone())
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -136,6 +429,14 @@ pub struct PathSpec {
     pub summary: Option<String>,
     pub description: Option<String>,
 
+    /// Overrides the top level servers for all operations of this path.
+    #[serde(default)]
+    pub servers: Vec<ServerSpec>,
+
+    /// Parameters of all operations of this path, which can override them.
+    #[serde(default)]
+    pub parameters: Vec<ParamSpec>,
+
     pub get: Option<OperationSpec>,
     pub post: Option<OperationSpec>,
     pub put: Option<OperationSpec>,
@@ -146,6 +447,33 @@ pub struct PathSpec {
     pub options: Option<OperationSpec>,
 }
 
+impl PathSpec {
+    /// The operations of the path along with their lowercase methods.
+    pub fn operations(&self) -> Vec<(&'static str, &OperationSpec)> {
+        let methods = [
+            ("get", &self.get), ("post", &self.post), ("put", &self.put),
+            ("patch", &self.patch), ("delete", &self.delete), ("head", &self.head),
+            ("trace", &self.trace), ("options", &self.options),
+        ];
+
+        methods.iter()
+            .filter_map(|(method, op)| op.as_ref().map(|it| (*method, it)))
+            .collect()
+    }
+
+    /// The parameters of an operation of the path, including the ones of the
+    /// path unless the operation declares one with the same name and location.
+    pub fn params<'a>(&'a self, op: &'a OperationSpec) -> Vec<&'a ParamSpec> {
+        let mut params: Vec<&ParamSpec> = self.parameters.iter()
+            .filter(|it| {
+                !op.parameters.iter().any(|own| own.name == it.name && own.location == it.location)
+            })
+            .collect();
+        params.extend(op.parameters.iter());
+        params
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct OperationSpec {
     #[serde(default)]
@@ -160,10 +488,17 @@ pub struct OperationSpec {
 
     #[serde(rename = "requestBody")]
     pub request_body: Option<RefOr<RequestBodySpec>>,
-    pub responses: BTreeMap<String, ResponseSpec>,
+    pub responses: BTreeMap<String, RefOr<ResponseSpec>>,
 
     #[serde(default)]
     pub deprecated: bool,
+
+    /// Overrides the top level requirements, `Some(vec![])` removes them.
+    pub security: Option<Vec<SecurityRequirementSpec>>,
+
+    /// Overrides the path and top level servers.
+    #[serde(default)]
+    pub servers: Vec<ServerSpec>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -193,3 +528,69 @@ pub struct TagSpec {
     pub description: String,
 }
 
+
+/// Maps the names of security schemes to the scopes required from them. All
+/// schemes of a requirement have to be satisfied at once.
+pub type SecurityRequirementSpec = BTreeMap<String, Vec<String>>;
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum SecuritySchemeSpec {
+    #[serde(rename = "apiKey")]
+    ApiKey {
+        name: String,
+
+        #[serde(rename = "in")]
+        location: String,
+
+        description: Option<String>,
+    },
+    #[serde(rename = "http")]
+    Http {
+        scheme: String,
+
+        #[serde(rename = "bearerFormat")]
+        bearer_format: Option<String>,
+
+        description: Option<String>,
+    },
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        flows: Box<OAuthFlowsSpec>,
+        description: Option<String>,
+    },
+    #[serde(rename = "openIdConnect")]
+    OpenIdConnect {
+        #[serde(rename = "openIdConnectUrl")]
+        open_id_connect_url: String,
+
+        description: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OAuthFlowsSpec {
+    pub implicit: Option<OAuthFlowSpec>,
+    pub password: Option<OAuthFlowSpec>,
+
+    #[serde(rename = "clientCredentials")]
+    pub client_credentials: Option<OAuthFlowSpec>,
+
+    #[serde(rename = "authorizationCode")]
+    pub authorization_code: Option<OAuthFlowSpec>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OAuthFlowSpec {
+    #[serde(rename = "authorizationUrl")]
+    pub authorization_url: Option<String>,
+
+    #[serde(rename = "tokenUrl")]
+    pub token_url: Option<String>,
+
+    #[serde(rename = "refreshUrl")]
+    pub refresh_url: Option<String>,
+
+    #[serde(default)]
+    pub scopes: BTreeMap<String, String>,
+}
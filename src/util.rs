@@ -1,11 +1,27 @@
 use std::fs;
+use std::collections::{HashMap, HashSet};
 use tera::Tera;
 use serde_json;
 use crate::{Error, gen::TemplateContext};
 
+/// Loads the templates of a generator along with the ones in
+/// `templates/common` that all generators share.
 pub fn load_templates(path: &str) -> Result<Tera, Error> {
-    let path = format!("templates/{}/*.tera", path);
-    let result = Tera::new(&path)?;
+    load_templates_with(path, &[])
+}
+
+/// Loads the templates of a generator like `load_templates`, plus the ones
+/// of the given shared directories. Templates of the generator take
+/// precedence over shared ones of the same name.
+pub fn load_templates_with(path: &str, shared: &[&str]) -> Result<Tera, Error> {
+    // every directory may use the ones after it, so they are merged starting
+    // with the last one to satisfy the checks of each step
+    let mut result = Tera::parse("templates/common/*.tera")?;
+    for dir in shared.iter().rev().chain(&[path]) {
+        let mut templates = Tera::parse(&format!("templates/{}/*.tera", dir))?;
+        templates.extend(&result)?;
+        result = templates;
+    }
     Ok(result)
 }
 
@@ -29,3 +45,30 @@ where
 
     Ok(())
 }
+
+/// Finds the nodes of a directed graph that are part of a cycle, given the
+/// edges of every node. The graphs are small, so this simply checks for
+/// every node whether it can reach itself.
+pub fn cyclic_nodes(edges: &HashMap<String, Vec<String>>) -> HashSet<String> {
+    edges.keys()
+        .filter(|start| {
+            let mut visited: HashSet<&str> = HashSet::new();
+            let mut pending: Vec<&str> = edges[start.as_str()].iter().map(|it| it.as_str()).collect();
+
+            while let Some(node) = pending.pop() {
+                if node == start.as_str() {
+                    return true;
+                }
+
+                if visited.insert(node) {
+                    if let Some(next) = edges.get(node) {
+                        pending.extend(next.iter().map(|it| it.as_str()));
+                    }
+                }
+            }
+
+            false
+        })
+        .cloned()
+        .collect()
+}